@@ -6,6 +6,36 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * Connection state kind
+ */
+typedef enum lb_connection_state_kind_t {
+  /**
+   * Connecting to the server
+   */
+  ConnectionStateConnecting,
+  /**
+   * Connected, but not yet authenticated
+   */
+  ConnectionStateConnected,
+  /**
+   * Authenticated, push events will be received
+   */
+  ConnectionStateAuthenticated,
+  /**
+   * Disconnected from the server
+   */
+  ConnectionStateDisconnected,
+  /**
+   * Reconnecting to the server
+   */
+  ConnectionStateReconnecting,
+  /**
+   * Closed, will not reconnect
+   */
+  ConnectionStateClosed,
+} lb_connection_state_kind_t;
+
 /**
  * Language identifer
  */
@@ -405,12 +435,150 @@ typedef struct lb_push_order_changed_t {
    * Remark message
    */
   const char *remark;
+  /**
+   * `true` if this event was synthesized from the order list fetched after
+   * a reconnect
+   */
+  bool recovered;
 } lb_push_order_changed_t;
 
 typedef void (*lb_order_changed_callback_t)(const struct lb_trade_context_t*,
                                             const struct lb_push_order_changed_t*,
                                             void*);
 
+/**
+ * Connection state
+ */
+typedef struct lb_connection_state_t {
+  /**
+   * State kind
+   */
+  enum lb_connection_state_kind_t kind;
+  /**
+   * Close code (maybe null, only for `Disconnected`)
+   */
+  const uint16_t *reason_code;
+  /**
+   * Close reason (maybe null, only for `Disconnected`)
+   */
+  const char *reason_message;
+  /**
+   * Reconnect attempt, starting from `1` (maybe null, only for
+   * `Reconnecting`)
+   */
+  const uint32_t *attempt;
+  /**
+   * The error that closed the context (maybe null, only for `Closed`)
+   */
+  const char *error;
+} lb_connection_state_t;
+
+typedef void (*lb_connection_state_callback_t)(const struct lb_trade_context_t*,
+                                               const struct lb_connection_state_t*,
+                                               void*);
+
+/**
+ * Push event not known by this version of the SDK
+ */
+typedef struct lb_push_raw_t {
+  /**
+   * Topic of the notification
+   */
+  const char *topic;
+  /**
+   * Event name
+   */
+  const char *event;
+  /**
+   * Event data in JSON
+   */
+  const char *data;
+} lb_push_raw_t;
+
+typedef void (*lb_raw_push_callback_t)(const struct lb_trade_context_t*,
+                                       const struct lb_push_raw_t*,
+                                       void*);
+
+/**
+ * Cash info
+ */
+typedef struct lb_cash_info_t {
+  /**
+   * Withdraw cash
+   */
+  const struct lb_decimal_t *withdraw_cash;
+  /**
+   * Available cash
+   */
+  const struct lb_decimal_t *available_cash;
+  /**
+   * Frozen cash
+   */
+  const struct lb_decimal_t *frozen_cash;
+  /**
+   * Cash to be settled
+   */
+  const struct lb_decimal_t *settling_cash;
+  /**
+   * Currency
+   */
+  const char *currency;
+} lb_cash_info_t;
+
+/**
+ * Account balance
+ */
+typedef struct lb_account_balance_t {
+  /**
+   * Total cash
+   */
+  const struct lb_decimal_t *total_cash;
+  /**
+   * Maximum financing amount
+   */
+  const struct lb_decimal_t *max_finance_amount;
+  /**
+   * Remaining financing amount
+   */
+  const struct lb_decimal_t *remaining_finance_amount;
+  /**
+   * Risk control level
+   */
+  int32_t risk_level;
+  /**
+   * Margin call
+   */
+  const struct lb_decimal_t *margin_call;
+  /**
+   * Currency
+   */
+  const char *currency;
+  /**
+   * Cash details
+   */
+  const struct lb_cash_info_t *cash_infos;
+  /**
+   * Number of cash details
+   */
+  uintptr_t num_cash_infos;
+  /**
+   * Net assets
+   */
+  const struct lb_decimal_t *net_assets;
+  /**
+   * Initial margin
+   */
+  const struct lb_decimal_t *init_margin;
+  /**
+   * Maintenance margin
+   */
+  const struct lb_decimal_t *maintenance_margin;
+  /**
+   * Buy power
+   */
+  const struct lb_decimal_t *buy_power;
+} lb_account_balance_t;
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -525,6 +693,39 @@ void lb_trade_context_set_on_order_changed(const struct lb_trade_context_t *ctx,
                                            void *userdata,
                                            lb_free_userdata_func_t free_userdata);
 
+/**
+ * Set connection state callback, it will be called when the connection state
+ * changes.
+ */
+void lb_trade_context_set_on_connection_state(const struct lb_trade_context_t *ctx,
+                                              lb_connection_state_callback_t callback,
+                                              void *userdata,
+                                              lb_free_userdata_func_t free_userdata);
+
+/**
+ * Set raw push callback, it will be called when receiving a push event not
+ * known by this version of the SDK.
+ */
+void lb_trade_context_set_on_raw_push(const struct lb_trade_context_t *ctx,
+                                      lb_raw_push_callback_t callback,
+                                      void *userdata,
+                                      lb_free_userdata_func_t free_userdata);
+
+/**
+ * Returns the kind of the current connection state
+ */
+enum lb_connection_state_kind_t lb_trade_context_state(const struct lb_trade_context_t *ctx);
+
+/**
+ * Gracefully close the trade context
+ *
+ * Unsubscribes all topics, waits for the pending requests and closes the
+ * connection. The requests sent after this will fail.
+ */
+void lb_trade_context_close(const struct lb_trade_context_t *ctx,
+                            lb_async_callback_t callback,
+                            void *userdata);
+
 void lb_trade_context_subscribe(const struct lb_trade_context_t *ctx,
                                 const enum lb_topic_type_t *topics,
                                 uintptr_t num_topics,
@@ -537,6 +738,15 @@ void lb_trade_context_unsubscribe(const struct lb_trade_context_t *ctx,
                                   lb_async_callback_t callback,
                                   void *userdata);
 
+/**
+ * Get account balance
+ */
+void lb_trade_context_account_balance(const struct lb_trade_context_t *ctx,
+                                      const char *account_no,
+                                      const char *currency,
+                                      lb_async_callback_t callback,
+                                      void *userdata);
+
 /**
  * Create a decimal value with a 64 bit `m` representation and corresponding
  * `e` scale.
@@ -1,4 +1,8 @@
-use std::{ffi::c_void, sync::Arc};
+use std::{
+    ffi::{c_void, CStr},
+    os::raw::c_char,
+    sync::Arc,
+};
 
 use longportwhale::{trade::PushEvent, TradeContext};
 use parking_lot::Mutex;
@@ -8,18 +12,28 @@ use crate::{
     callback::{CFreeUserDataFunc, Callback},
     config::CConfig,
     trade_context::{
-        enum_types::CTopicType,
-        types::{CPushOrderChanged, CPushOrderChangedOwned},
+        enum_types::{CConnectionStateKind, CTopicType},
+        types::{
+            CAccountBalanceOwned, CConnectionState, CConnectionStateOwned, CPushOrderChanged,
+            CPushOrderChangedOwned, CPushRaw, CPushRawOwned,
+        },
     },
-    types::ToFFI,
+    types::{CCow, ToFFI},
 };
 
 pub type COnOrderChangedCallback =
     extern "C" fn(*const CTradeContext, *const CPushOrderChanged, *mut c_void);
 
+pub type COnConnectionStateCallback =
+    extern "C" fn(*const CTradeContext, *const CConnectionState, *mut c_void);
+
+pub type COnRawPushCallback = extern "C" fn(*const CTradeContext, *const CPushRaw, *mut c_void);
+
 #[derive(Default)]
 struct Callbacks {
     order_changed: Option<Callback<COnOrderChangedCallback>>,
+    connection_state: Option<Callback<COnConnectionStateCallback>>,
+    raw_push: Option<Callback<COnRawPushCallback>>,
 }
 
 pub struct CTradeContextState {
@@ -89,6 +103,28 @@ pub unsafe extern "C" fn lb_trade_context_new(
                                 );
                             }
                         }
+                        PushEvent::Execution(_) => {}
+                        PushEvent::ConnectionState(connection_state) => {
+                            if let Some(callback) = &state.callbacks.connection_state {
+                                let connection_state_owned: CConnectionStateOwned =
+                                    connection_state.into();
+                                (callback.f)(
+                                    Arc::as_ptr(&ctx),
+                                    &connection_state_owned.to_ffi_type(),
+                                    callback.userdata,
+                                );
+                            }
+                        }
+                        PushEvent::Unknown { topic, event, data } => {
+                            if let Some(callback) = &state.callbacks.raw_push {
+                                let raw_owned = CPushRawOwned::new(topic, event, data.to_string());
+                                (callback.f)(
+                                    Arc::as_ptr(&ctx),
+                                    &raw_owned.to_ffi_type(),
+                                    callback.userdata,
+                                );
+                            }
+                        }
                     }
                 }
             });
@@ -158,6 +194,61 @@ pub unsafe extern "C" fn lb_trade_context_set_on_order_changed(
     });
 }
 
+/// Set connection state callback, it will be called when the connection state
+/// changes.
+#[no_mangle]
+pub unsafe extern "C" fn lb_trade_context_set_on_connection_state(
+    ctx: *const CTradeContext,
+    callback: COnConnectionStateCallback,
+    userdata: *mut c_void,
+    free_userdata: CFreeUserDataFunc,
+) {
+    (*ctx).state.lock().callbacks.connection_state = Some(Callback {
+        f: callback,
+        userdata,
+        free_userdata,
+    });
+}
+
+/// Set raw push callback, it will be called when receiving a push event not
+/// known by this version of the SDK.
+#[no_mangle]
+pub unsafe extern "C" fn lb_trade_context_set_on_raw_push(
+    ctx: *const CTradeContext,
+    callback: COnRawPushCallback,
+    userdata: *mut c_void,
+    free_userdata: CFreeUserDataFunc,
+) {
+    (*ctx).state.lock().callbacks.raw_push = Some(Callback {
+        f: callback,
+        userdata,
+        free_userdata,
+    });
+}
+
+/// Returns the kind of the current connection state
+#[no_mangle]
+pub unsafe extern "C" fn lb_trade_context_state(ctx: *const CTradeContext) -> CConnectionStateKind {
+    (&(*ctx).ctx.state()).into()
+}
+
+/// Gracefully close the trade context
+///
+/// Unsubscribes all topics, waits for the pending requests and closes the
+/// connection. The requests sent after this will fail.
+#[no_mangle]
+pub unsafe extern "C" fn lb_trade_context_close(
+    ctx: *const CTradeContext,
+    callback: CAsyncCallback,
+    userdata: *mut c_void,
+) {
+    let ctx_inner = (*ctx).ctx.clone();
+    execute_async(callback, ctx, userdata, async move {
+        ctx_inner.close().await;
+        Ok(())
+    });
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn lb_trade_context_subscribe(
     ctx: *const CTradeContext,
@@ -195,3 +286,28 @@ pub unsafe extern "C" fn lb_trade_context_unsubscribe(
         ctx_inner.unsubscribe(topics).await
     });
 }
+
+/// Get account balance
+#[no_mangle]
+pub unsafe extern "C" fn lb_trade_context_account_balance(
+    ctx: *const CTradeContext,
+    account_no: *const c_char,
+    currency: *const c_char,
+    callback: CAsyncCallback,
+    userdata: *mut c_void,
+) {
+    let ctx_inner = (*ctx).ctx.clone();
+    let account_no = CStr::from_ptr(account_no)
+        .to_str()
+        .expect("invalid account no")
+        .to_string();
+    let currency = CStr::from_ptr(currency)
+        .to_str()
+        .expect("invalid currency")
+        .to_string();
+    execute_async(callback, ctx, userdata, async move {
+        let resp: CCow<CAccountBalanceOwned> =
+            CCow::new(ctx_inner.account_balance(account_no, currency).await?);
+        Ok(resp)
+    });
+}
@@ -197,3 +197,41 @@ pub enum CTriggerStatus {
     #[c(remote = "Released")]
     TriggerStatusReleased,
 }
+
+/// Connection state kind
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+#[repr(C)]
+pub enum CConnectionStateKind {
+    /// Connecting to the server
+    ConnectionStateConnecting,
+    /// Connected, but not yet authenticated
+    ConnectionStateConnected,
+    /// Authenticated, push events will be received
+    ConnectionStateAuthenticated,
+    /// Disconnected from the server
+    ConnectionStateDisconnected,
+    /// Reconnecting to the server
+    ConnectionStateReconnecting,
+    /// Closed, will not reconnect
+    ConnectionStateClosed,
+}
+
+impl From<&longportwhale::trade::ConnectionState> for CConnectionStateKind {
+    fn from(state: &longportwhale::trade::ConnectionState) -> Self {
+        use longportwhale::trade::ConnectionState;
+
+        match state {
+            ConnectionState::Connecting => CConnectionStateKind::ConnectionStateConnecting,
+            ConnectionState::Connected => CConnectionStateKind::ConnectionStateConnected,
+            ConnectionState::Authenticated => CConnectionStateKind::ConnectionStateAuthenticated,
+            ConnectionState::Disconnected { .. } => {
+                CConnectionStateKind::ConnectionStateDisconnected
+            }
+            ConnectionState::Reconnecting { .. } => {
+                CConnectionStateKind::ConnectionStateReconnecting
+            }
+            ConnectionState::Closed { .. } => CConnectionStateKind::ConnectionStateClosed,
+        }
+    }
+}
@@ -1,11 +1,16 @@
 use std::os::raw::c_char;
 
-use longportwhale::trade::{OrderSide, OrderStatus, OrderTag, OrderType, PushOrderChanged};
+use longportwhale::trade::{
+    AccountBalance, CashInfo, ConnectionState, OrderSide, OrderStatus, OrderTag, OrderType,
+    PushOrderChanged,
+};
 use time::OffsetDateTime;
 
 use crate::{
-    trade_context::enum_types::{COrderSide, COrderStatus, COrderTag, COrderType, CTriggerStatus},
-    types::{CDecimal, CString, ToFFI},
+    trade_context::enum_types::{
+        CConnectionStateKind, COrderSide, COrderStatus, COrderTag, COrderType, CTriggerStatus,
+    },
+    types::{CDecimal, CString, CVec, ToFFI},
 };
 
 /// Order changed message
@@ -61,6 +66,9 @@ pub struct CPushOrderChanged {
     pub last_price: *const CDecimal,
     /// Remark message
     pub remark: *const c_char,
+    /// `true` if this event was synthesized from the order list fetched after
+    /// a reconnect
+    pub recovered: bool,
 }
 
 pub struct CPushOrderChangedOwned {
@@ -90,6 +98,7 @@ pub struct CPushOrderChangedOwned {
     last_price: Option<CDecimal>,
     /// Remark message
     pub remark: CString,
+    recovered: bool,
 }
 
 impl From<PushOrderChanged> for CPushOrderChangedOwned {
@@ -120,12 +129,13 @@ impl From<PushOrderChanged> for CPushOrderChangedOwned {
             last_share,
             last_price,
             remark,
+            recovered,
         } = order_changed;
         CPushOrderChangedOwned {
             side,
             stock_name: stock_name.into(),
             submitted_quantity,
-            symbol: symbol.into(),
+            symbol: symbol.to_string().into(),
             order_type,
             submitted_price: submitted_price.into(),
             executed_quantity,
@@ -147,6 +157,7 @@ impl From<PushOrderChanged> for CPushOrderChangedOwned {
             last_share: last_share.map(Into::into),
             last_price: last_price.map(Into::into),
             remark: remark.into(),
+            recovered,
         }
     }
 }
@@ -181,6 +192,7 @@ impl ToFFI for CPushOrderChangedOwned {
             last_share,
             last_price,
             remark,
+            recovered,
         } = self;
         CPushOrderChanged {
             side: (*side).into(),
@@ -235,6 +247,296 @@ impl ToFFI for CPushOrderChangedOwned {
                 .map(ToFFI::to_ffi_type)
                 .unwrap_or(std::ptr::null()),
             remark: remark.to_ffi_type(),
+            recovered: *recovered,
+        }
+    }
+}
+
+/// Cash info
+#[repr(C)]
+pub struct CCashInfo {
+    /// Withdraw cash
+    pub withdraw_cash: *const CDecimal,
+    /// Available cash
+    pub available_cash: *const CDecimal,
+    /// Frozen cash
+    pub frozen_cash: *const CDecimal,
+    /// Cash to be settled
+    pub settling_cash: *const CDecimal,
+    /// Currency
+    pub currency: *const c_char,
+}
+
+#[derive(Debug)]
+pub(crate) struct CCashInfoOwned {
+    withdraw_cash: CDecimal,
+    available_cash: CDecimal,
+    frozen_cash: CDecimal,
+    settling_cash: CDecimal,
+    currency: CString,
+}
+
+impl From<CashInfo> for CCashInfoOwned {
+    fn from(info: CashInfo) -> Self {
+        let CashInfo {
+            withdraw_cash,
+            available_cash,
+            frozen_cash,
+            settling_cash,
+            currency,
+        } = info;
+        CCashInfoOwned {
+            withdraw_cash: withdraw_cash.into(),
+            available_cash: available_cash.into(),
+            frozen_cash: frozen_cash.into(),
+            settling_cash: settling_cash.into(),
+            currency: currency.into(),
+        }
+    }
+}
+
+impl ToFFI for CCashInfoOwned {
+    type FFIType = CCashInfo;
+
+    fn to_ffi_type(&self) -> Self::FFIType {
+        let CCashInfoOwned {
+            withdraw_cash,
+            available_cash,
+            frozen_cash,
+            settling_cash,
+            currency,
+        } = self;
+        CCashInfo {
+            withdraw_cash: withdraw_cash.to_ffi_type(),
+            available_cash: available_cash.to_ffi_type(),
+            frozen_cash: frozen_cash.to_ffi_type(),
+            settling_cash: settling_cash.to_ffi_type(),
+            currency: currency.to_ffi_type(),
+        }
+    }
+}
+
+/// Account balance
+#[repr(C)]
+pub struct CAccountBalance {
+    /// Total cash
+    pub total_cash: *const CDecimal,
+    /// Maximum financing amount
+    pub max_finance_amount: *const CDecimal,
+    /// Remaining financing amount
+    pub remaining_finance_amount: *const CDecimal,
+    /// Risk control level
+    pub risk_level: i32,
+    /// Margin call
+    pub margin_call: *const CDecimal,
+    /// Currency
+    pub currency: *const c_char,
+    /// Cash details
+    pub cash_infos: *const CCashInfo,
+    /// Number of cash details
+    pub num_cash_infos: usize,
+    /// Net assets
+    pub net_assets: *const CDecimal,
+    /// Initial margin
+    pub init_margin: *const CDecimal,
+    /// Maintenance margin
+    pub maintenance_margin: *const CDecimal,
+    /// Buy power
+    pub buy_power: *const CDecimal,
+}
+
+#[derive(Debug)]
+pub(crate) struct CAccountBalanceOwned {
+    total_cash: CDecimal,
+    max_finance_amount: CDecimal,
+    remaining_finance_amount: CDecimal,
+    risk_level: i32,
+    margin_call: CDecimal,
+    currency: CString,
+    cash_infos: CVec<CCashInfoOwned>,
+    net_assets: CDecimal,
+    init_margin: CDecimal,
+    maintenance_margin: CDecimal,
+    buy_power: CDecimal,
+}
+
+impl From<AccountBalance> for CAccountBalanceOwned {
+    fn from(balance: AccountBalance) -> Self {
+        let AccountBalance {
+            total_cash,
+            max_finance_amount,
+            remaining_finance_amount,
+            risk_level,
+            margin_call,
+            currency,
+            cash_infos,
+            net_assets,
+            init_margin,
+            maintenance_margin,
+            buy_power,
+        } = balance;
+        CAccountBalanceOwned {
+            total_cash: total_cash.into(),
+            max_finance_amount: max_finance_amount.into(),
+            remaining_finance_amount: remaining_finance_amount.into(),
+            risk_level,
+            margin_call: margin_call.into(),
+            currency: currency.into(),
+            cash_infos: cash_infos.into(),
+            net_assets: net_assets.into(),
+            init_margin: init_margin.into(),
+            maintenance_margin: maintenance_margin.into(),
+            buy_power: buy_power.into(),
+        }
+    }
+}
+
+impl ToFFI for CAccountBalanceOwned {
+    type FFIType = CAccountBalance;
+
+    fn to_ffi_type(&self) -> Self::FFIType {
+        let CAccountBalanceOwned {
+            total_cash,
+            max_finance_amount,
+            remaining_finance_amount,
+            risk_level,
+            margin_call,
+            currency,
+            cash_infos,
+            net_assets,
+            init_margin,
+            maintenance_margin,
+            buy_power,
+        } = self;
+        CAccountBalance {
+            total_cash: total_cash.to_ffi_type(),
+            max_finance_amount: max_finance_amount.to_ffi_type(),
+            remaining_finance_amount: remaining_finance_amount.to_ffi_type(),
+            risk_level: *risk_level,
+            margin_call: margin_call.to_ffi_type(),
+            currency: currency.to_ffi_type(),
+            cash_infos: cash_infos.to_ffi_type(),
+            num_cash_infos: cash_infos.len(),
+            net_assets: net_assets.to_ffi_type(),
+            init_margin: init_margin.to_ffi_type(),
+            maintenance_margin: maintenance_margin.to_ffi_type(),
+            buy_power: buy_power.to_ffi_type(),
+        }
+    }
+}
+
+/// Connection state
+#[repr(C)]
+pub struct CConnectionState {
+    /// State kind
+    pub kind: CConnectionStateKind,
+    /// Close code (maybe null, only for `Disconnected`)
+    pub reason_code: *const u16,
+    /// Close reason (maybe null, only for `Disconnected`)
+    pub reason_message: *const c_char,
+    /// Reconnect attempt, starting from `1` (maybe null, only for
+    /// `Reconnecting`)
+    pub attempt: *const u32,
+    /// The error that closed the context (maybe null, only for `Closed`)
+    pub error: *const c_char,
+}
+
+pub struct CConnectionStateOwned {
+    kind: CConnectionStateKind,
+    reason_code: Option<u16>,
+    reason_message: Option<CString>,
+    attempt: Option<u32>,
+    error: Option<CString>,
+}
+
+impl From<ConnectionState> for CConnectionStateOwned {
+    fn from(state: ConnectionState) -> Self {
+        let kind = CConnectionStateKind::from(&state);
+        let (reason, attempt, error) = match state {
+            ConnectionState::Disconnected { reason } => (reason, None, None),
+            ConnectionState::Reconnecting { attempt } => (None, Some(attempt), None),
+            ConnectionState::Closed { error } => (None, None, error),
+            _ => (None, None, None),
+        };
+        CConnectionStateOwned {
+            kind,
+            reason_code: reason.as_ref().map(|reason| reason.code.into()),
+            reason_message: reason.map(|reason| reason.message.into()),
+            attempt,
+            error: error.map(|err| err.to_string().into()),
+        }
+    }
+}
+
+impl ToFFI for CConnectionStateOwned {
+    type FFIType = CConnectionState;
+
+    fn to_ffi_type(&self) -> Self::FFIType {
+        let CConnectionStateOwned {
+            kind,
+            reason_code,
+            reason_message,
+            attempt,
+            error,
+        } = self;
+        CConnectionState {
+            kind: *kind,
+            reason_code: reason_code
+                .as_ref()
+                .map(|value| value as *const u16)
+                .unwrap_or(std::ptr::null()),
+            reason_message: reason_message
+                .as_ref()
+                .map(ToFFI::to_ffi_type)
+                .unwrap_or(std::ptr::null()),
+            attempt: attempt
+                .as_ref()
+                .map(|value| value as *const u32)
+                .unwrap_or(std::ptr::null()),
+            error: error
+                .as_ref()
+                .map(ToFFI::to_ffi_type)
+                .unwrap_or(std::ptr::null()),
+        }
+    }
+}
+
+/// Push event not known by this version of the SDK
+#[repr(C)]
+pub struct CPushRaw {
+    /// Topic of the notification
+    pub topic: *const c_char,
+    /// Event name
+    pub event: *const c_char,
+    /// Event data in JSON
+    pub data: *const c_char,
+}
+
+pub struct CPushRawOwned {
+    topic: CString,
+    event: CString,
+    data: CString,
+}
+
+impl CPushRawOwned {
+    pub(crate) fn new(topic: String, event: String, data: String) -> Self {
+        CPushRawOwned {
+            topic: topic.into(),
+            event: event.into(),
+            data: data.into(),
+        }
+    }
+}
+
+impl ToFFI for CPushRawOwned {
+    type FFIType = CPushRaw;
+
+    fn to_ffi_type(&self) -> Self::FFIType {
+        let CPushRawOwned { topic, event, data } = self;
+        CPushRaw {
+            topic: topic.to_ffi_type(),
+            event: event.to_ffi_type(),
+            data: data.to_ffi_type(),
         }
     }
 }
@@ -7,6 +7,7 @@ mod language;
 mod option;
 mod string;
 
+pub(crate) use array::CVec;
 pub(crate) use cow::CCow;
 pub(crate) use decimal::CDecimal;
 pub(crate) use language::CLanguage;
@@ -43,6 +43,30 @@ public:
   /// will call back to this function.
   void set_on_order_changed(
     PushCallback<TradeContext, PushOrderChanged> callback) const;
+
+  /// Set connection state callback, it will be called when the connection
+  /// state changes.
+  void set_on_connection_state(
+    PushCallback<TradeContext, ConnectionState> callback) const;
+
+  /// Set raw push callback, it will be called when receiving a push event not
+  /// known by this version of the SDK.
+  void set_on_raw_push(PushCallback<TradeContext, PushRaw> callback) const;
+
+  /// Returns the kind of the current connection state
+  ConnectionStateKind state() const;
+
+  /// Gracefully close the trade context
+  ///
+  /// Unsubscribes all topics, waits for the pending requests and closes the
+  /// connection. The requests sent after this will fail.
+  void close(AsyncCallback<TradeContext, void> callback) const;
+
+  /// Get account balance
+  void account_balance(
+    const std::string& account_no,
+    const std::string& currency,
+    AsyncCallback<TradeContext, AccountBalance> callback) const;
 };
 
 } // namespace trade
@@ -2,6 +2,8 @@
 
 #include "decimal.hpp"
 #include <optional>
+#include <string>
+#include <vector>
 
 namespace longportwhale {
 
@@ -236,6 +238,94 @@ struct PushOrderChanged
   std::optional<Decimal> last_price;
   /// Remark message
   std::string remark;
+  /// `true` if this event was synthesized from the order list fetched after a
+  /// reconnect
+  bool recovered;
+};
+
+/// Cash info
+struct CashInfo
+{
+  /// Withdraw cash
+  Decimal withdraw_cash;
+  /// Available cash
+  Decimal available_cash;
+  /// Frozen cash
+  Decimal frozen_cash;
+  /// Cash to be settled
+  Decimal settling_cash;
+  /// Currency
+  std::string currency;
+};
+
+/// Account balance
+struct AccountBalance
+{
+  /// Total cash
+  Decimal total_cash;
+  /// Maximum financing amount
+  Decimal max_finance_amount;
+  /// Remaining financing amount
+  Decimal remaining_finance_amount;
+  /// Risk control level
+  int32_t risk_level;
+  /// Margin call
+  Decimal margin_call;
+  /// Currency
+  std::string currency;
+  /// Cash details
+  std::vector<CashInfo> cash_infos;
+  /// Net assets
+  Decimal net_assets;
+  /// Initial margin
+  Decimal init_margin;
+  /// Maintenance margin
+  Decimal maintenance_margin;
+  /// Buy power
+  Decimal buy_power;
+};
+
+/// Connection state kind
+enum class ConnectionStateKind
+{
+  /// Connecting to the server
+  Connecting,
+  /// Connected, but not yet authenticated
+  Connected,
+  /// Authenticated, push events will be received
+  Authenticated,
+  /// Disconnected from the server
+  Disconnected,
+  /// Reconnecting to the server
+  Reconnecting,
+  /// Closed, will not reconnect
+  Closed,
+};
+
+/// Connection state
+struct ConnectionState
+{
+  /// State kind
+  ConnectionStateKind kind;
+  /// Close code (only for `Disconnected`)
+  std::optional<uint16_t> reason_code;
+  /// Close reason (only for `Disconnected`)
+  std::optional<std::string> reason_message;
+  /// Reconnect attempt, starting from `1` (only for `Reconnecting`)
+  std::optional<uint32_t> attempt;
+  /// The error that closed the context (only for `Closed`)
+  std::optional<std::string> error;
+};
+
+/// Push event not known by this version of the SDK
+struct PushRaw
+{
+  /// Topic of the notification
+  std::string topic;
+  /// Event name
+  std::string event;
+  /// Event data in JSON
+  std::string data;
 };
 } // namespace trade
 
@@ -2,16 +2,23 @@
 
 #include "longportwhale.h"
 #include "types.hpp"
+#include <algorithm>
+#include <iterator>
 #include <stdexcept>
 
 namespace longportwhale {
 namespace convert {
 
+using longportwhale::trade::AccountBalance;
+using longportwhale::trade::CashInfo;
+using longportwhale::trade::ConnectionState;
+using longportwhale::trade::ConnectionStateKind;
 using longportwhale::trade::OrderSide;
 using longportwhale::trade::OrderStatus;
 using longportwhale::trade::OrderTag;
 using longportwhale::trade::OrderType;
 using longportwhale::trade::PushOrderChanged;
+using longportwhale::trade::PushRaw;
 using longportwhale::trade::TopicType;
 using longportwhale::trade::TriggerStatus;
 
@@ -319,8 +326,83 @@ convert(const lb_push_order_changed_t* info)
     info->last_price ? std::optional{ Decimal(info->last_price) }
                      : std::nullopt,
     info->remark,
+    info->recovered,
   };
 }
 
+inline CashInfo
+convert(const lb_cash_info_t* info)
+{
+  return CashInfo{
+    Decimal(info->withdraw_cash), Decimal(info->available_cash),
+    Decimal(info->frozen_cash),   Decimal(info->settling_cash),
+    info->currency,
+  };
+}
+
+inline AccountBalance
+convert(const lb_account_balance_t* info)
+{
+  std::vector<CashInfo> cash_infos;
+  std::transform(info->cash_infos,
+                 info->cash_infos + info->num_cash_infos,
+                 std::back_inserter(cash_infos),
+                 [](auto item) { return convert(&item); });
+
+  return AccountBalance{
+    Decimal(info->total_cash),
+    Decimal(info->max_finance_amount),
+    Decimal(info->remaining_finance_amount),
+    info->risk_level,
+    Decimal(info->margin_call),
+    info->currency,
+    cash_infos,
+    Decimal(info->net_assets),
+    Decimal(info->init_margin),
+    Decimal(info->maintenance_margin),
+    Decimal(info->buy_power),
+  };
+}
+
+inline ConnectionStateKind
+convert(lb_connection_state_kind_t kind)
+{
+  switch (kind) {
+    case ConnectionStateConnecting:
+      return ConnectionStateKind::Connecting;
+    case ConnectionStateConnected:
+      return ConnectionStateKind::Connected;
+    case ConnectionStateAuthenticated:
+      return ConnectionStateKind::Authenticated;
+    case ConnectionStateDisconnected:
+      return ConnectionStateKind::Disconnected;
+    case ConnectionStateReconnecting:
+      return ConnectionStateKind::Reconnecting;
+    case ConnectionStateClosed:
+      return ConnectionStateKind::Closed;
+    default:
+      throw std::invalid_argument("unreachable");
+  }
+}
+
+inline ConnectionState
+convert(const lb_connection_state_t* info)
+{
+  return ConnectionState{
+    convert(info->kind),
+    info->reason_code ? std::optional{ *info->reason_code } : std::nullopt,
+    info->reason_message ? std::optional{ std::string(info->reason_message) }
+                         : std::nullopt,
+    info->attempt ? std::optional{ *info->attempt } : std::nullopt,
+    info->error ? std::optional{ std::string(info->error) } : std::nullopt,
+  };
+}
+
+inline PushRaw
+convert(const lb_push_raw_t* info)
+{
+  return PushRaw{ info->topic, info->event, info->data };
+}
+
 } // namespace convert
 } // namespace longport
@@ -144,5 +144,91 @@ TradeContext::set_on_order_changed(
     [](auto p) { delete (PushCallback<TradeContext, PushOrderChanged>*)p; });
 }
 
+void
+TradeContext::set_on_connection_state(
+  PushCallback<TradeContext, ConnectionState> callback) const
+{
+  lb_trade_context_set_on_connection_state(
+    ctx_,
+    [](auto ctx, auto event, auto userdata) {
+      auto callback_ptr =
+        callback::get_push_callback<TradeContext, ConnectionState>(userdata);
+      ConnectionState event2 = convert(event);
+      (*callback_ptr)(
+        PushEvent<TradeContext, ConnectionState>(TradeContext(ctx), &event2));
+    },
+    new PushCallback<TradeContext, ConnectionState>(callback),
+    [](auto p) { delete (PushCallback<TradeContext, ConnectionState>*)p; });
+}
+
+void
+TradeContext::set_on_raw_push(PushCallback<TradeContext, PushRaw> callback) const
+{
+  lb_trade_context_set_on_raw_push(
+    ctx_,
+    [](auto ctx, auto event, auto userdata) {
+      auto callback_ptr =
+        callback::get_push_callback<TradeContext, PushRaw>(userdata);
+      PushRaw event2 = convert(event);
+      (*callback_ptr)(
+        PushEvent<TradeContext, PushRaw>(TradeContext(ctx), &event2));
+    },
+    new PushCallback<TradeContext, PushRaw>(callback),
+    [](auto p) { delete (PushCallback<TradeContext, PushRaw>*)p; });
+}
+
+ConnectionStateKind
+TradeContext::state() const
+{
+  return convert(lb_trade_context_state(ctx_));
+}
+
+void
+TradeContext::close(AsyncCallback<TradeContext, void> callback) const
+{
+  lb_trade_context_close(
+    ctx_,
+    [](auto res) {
+      auto callback_ptr =
+        callback::get_async_callback<TradeContext, void>(res->userdata);
+      (*callback_ptr)(AsyncResult<TradeContext, void>(
+        TradeContext((const lb_trade_context_t*)res->ctx),
+        Status(res->error),
+        nullptr));
+    },
+    new AsyncCallback<TradeContext, void>(callback));
+}
+
+void
+TradeContext::account_balance(
+  const std::string& account_no,
+  const std::string& currency,
+  AsyncCallback<TradeContext, AccountBalance> callback) const
+{
+  lb_trade_context_account_balance(
+    ctx_,
+    account_no.c_str(),
+    currency.c_str(),
+    [](auto res) {
+      auto callback_ptr =
+        callback::get_async_callback<TradeContext, AccountBalance>(
+          res->userdata);
+      auto* ctx_ptr = (const lb_trade_context_t*)res->ctx;
+      Status status(res->error);
+
+      if (status) {
+        AccountBalance resp =
+          convert((const lb_account_balance_t*)res->data);
+
+        (*callback_ptr)(AsyncResult<TradeContext, AccountBalance>(
+          TradeContext(ctx_ptr), std::move(status), &resp));
+      } else {
+        (*callback_ptr)(AsyncResult<TradeContext, AccountBalance>(
+          TradeContext(ctx_ptr), std::move(status), nullptr));
+      }
+    },
+    new AsyncCallback<TradeContext, AccountBalance>(callback));
+}
+
 } // namespace trade
 } // namespace longportwhale
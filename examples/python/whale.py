@@ -42,21 +42,18 @@ def main():
     tctx.subscribe([TopicType.Private])
     time.sleep(2) # Wait for subscription
 
-    # Test HTTP connection
-    logger.info("Testing HTTP connection...")
+    test_account = "L6VQEU00121996"
+
+    # Query account balance
+    logger.info("Querying account balance...")
     try:
-        asset_req = {
-            "account_no": test_account,
-            "currency": "HKD"
-        }
-        asset_res = httpcli.request(method="POST", path="/v1/whaleapi/asset/detail_info", body=asset_req, headers={})
-        logger.info(f"Asset query response: {asset_res}")
+        balance = tctx.account_balance(test_account, "HKD")
+        logger.info(f"Account balance: {balance}")
     except Exception as e:
         logger.error(f"Failed to query asset: {e}")
         # Continue to submit order to see if it persists
 
     # Submit Order
-    test_account = "L6VQEU00121996"
     req = {
         "symbol": "700.HK",
         "order_type": "MO",
@@ -1,26 +1,11 @@
 use longportwhale::{
     Config, TradeContext,
-    trade::{TopicType, PushEvent},
-    httpclient::{HttpClient, Method, Json},
+    trade::{
+        OrderSide, OrderTracker, OrderType, SubmitOrderOptions, SubscribeEventsOptions,
+        TimeInForceType, TopicType,
+    },
 };
-use std::sync::Arc;
-use serde::{Serialize, Deserialize};
-use tokio::sync::mpsc;
-
-#[derive(Debug, Serialize)]
-struct SubmitOrderRequest {
-    symbol: String,
-    order_type: String,
-    side: String,
-    submitted_quantity: String,
-    time_in_force: String,
-    account_no: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct SubmitOrderResponse {
-    order_id: String,
-}
+use std::{sync::Arc, time::Duration};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -38,61 +23,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ctx.subscribe([TopicType::Private]).await?;
     println!("Subscribed to Private events successfully.");
 
-    // Initialize HttpClient
-    let http_cli = HttpClient::from_env()?;
-    println!("HttpClient initialized.");
+    // Track the orders from the push events
+    let tracker = OrderTracker::from_events(ctx.subscribe_events(SubscribeEventsOptions::new()));
 
     // Spawn a task to listen for events
-    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
-    
     tokio::spawn(async move {
         while let Some(event) = receiver.recv().await {
             println!("Received event: {:?}", event);
-            if let PushEvent::OrderChanged(order_changed) = event {
-                let _ = event_tx.send(order_changed);
-            }
         }
     });
 
     // Submit order
-    let req = SubmitOrderRequest {
-        symbol: "700.HK".to_string(),
-        order_type: "MO".to_string(),
-        side: "Buy".to_string(),
-        submitted_quantity: "100".to_string(),
-        time_in_force: "Day".to_string(),
-        account_no: "L6VQEU00121996".to_string(),
-    };
+    let opts = SubmitOrderOptions::builder(
+        "L6VQEU00121996",
+        "700.HK".parse()?,
+        OrderType::MO,
+        OrderSide::Buy,
+        100,
+        TimeInForceType::Day,
+    )
+    .build()?;
 
-    println!("Submitting order: {:?}", req);
-    let resp: SubmitOrderResponse = http_cli.request(Method::POST, "/v1/whaleapi/trade/order")
-        .body(Json(req))
-        .response::<Json<SubmitOrderResponse>>()
-        .send()
-        .await?
-        .0;
+    println!("Submitting order: {:?}", opts);
+    let resp = ctx.submit_order(opts).await?;
     
     println!("Order submitted successfully. Order ID: {}", resp.order_id);
 
     // Wait for event
     println!("Waiting for order event...");
-    let timeout = tokio::time::sleep(tokio::time::Duration::from_secs(30));
-    tokio::pin!(timeout);
-
-    loop {
-        tokio::select! {
-            Some(order_event) = event_rx.recv() => {
-                if order_event.order_id == resp.order_id {
-                    println!("Received expected order event: {:?}", order_event);
-                    break;
-                } else {
-                    println!("Received other order event: {:?}", order_event);
-                }
-            }
-            _ = &mut timeout => {
-                println!("Timeout waiting for order event.");
-                return Err("Timeout waiting for order event".into());
-            }
+    match tracker
+        .wait_for(&resp.order_id, |_| true, Duration::from_secs(30))
+        .await
+    {
+        Some(order_event) => println!("Received expected order event: {:?}", order_event),
+        None => {
+            println!("Timeout waiting for order event.");
+            return Err("Timeout waiting for order event".into());
         }
     }
 
@@ -103,6 +103,38 @@ JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_freeTradeContext
 JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextSetOnOrderChanged
   (JNIEnv *, jclass, jlong, jobject);
 
+/*
+ * Class:     com_longportwhale_SdkNative
+ * Method:    tradeContextSetOnConnectionState
+ * Signature: (JLcom/longportwhale/trade/ConnectionStateHandler;)V
+ */
+JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextSetOnConnectionState
+  (JNIEnv *, jclass, jlong, jobject);
+
+/*
+ * Class:     com_longportwhale_SdkNative
+ * Method:    tradeContextSetOnRawPush
+ * Signature: (JLcom/longportwhale/trade/RawPushHandler;)V
+ */
+JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextSetOnRawPush
+  (JNIEnv *, jclass, jlong, jobject);
+
+/*
+ * Class:     com_longportwhale_SdkNative
+ * Method:    tradeContextState
+ * Signature: (J)Lcom/longportwhale/trade/ConnectionState;
+ */
+JNIEXPORT jobject JNICALL Java_com_longportwhale_SdkNative_tradeContextState
+  (JNIEnv *, jclass, jlong);
+
+/*
+ * Class:     com_longportwhale_SdkNative
+ * Method:    tradeContextClose
+ * Signature: (JLcom/longportwhale/AsyncCallback;)V
+ */
+JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextClose
+  (JNIEnv *, jclass, jlong, jobject);
+
 /*
  * Class:     com_longportwhale_SdkNative
  * Method:    tradeContextSubscribe
@@ -119,6 +151,14 @@ JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextSubscribe
 JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextUnsubscribe
   (JNIEnv *, jclass, jlong, jobjectArray, jobject);
 
+/*
+ * Class:     com_longportwhale_SdkNative
+ * Method:    tradeContextAccountBalance
+ * Signature: (JLjava/lang/String;Ljava/lang/String;Lcom/longportwhale/AsyncCallback;)V
+ */
+JNIEXPORT void JNICALL Java_com_longportwhale_SdkNative_tradeContextAccountBalance
+  (JNIEnv *, jclass, jlong, jstring, jstring, jobject);
+
 #ifdef __cplusplus
 }
 #endif
@@ -39,10 +39,21 @@ public class SdkNative {
 
         public static native void tradeContextSetOnOrderChanged(long context, OrderChangedHandler handler);
 
+        public static native void tradeContextSetOnConnectionState(long context, ConnectionStateHandler handler);
+
+        public static native void tradeContextSetOnRawPush(long context, RawPushHandler handler);
+
+        public static native ConnectionState tradeContextState(long context);
+
+        public static native void tradeContextClose(long context, AsyncCallback callback);
+
         public static native void tradeContextSubscribe(long context, TopicType[] topics, AsyncCallback callback);
 
         public static native void tradeContextUnsubscribe(long context, TopicType[] topics, AsyncCallback callback);
 
+        public static native void tradeContextAccountBalance(long context, String accountNo, String currency,
+                        AsyncCallback callback);
+
         static {
                 try {
                         NativeLoader.loadLibrary("longportwhale_java");
@@ -0,0 +1,52 @@
+package com.longportwhale.trade;
+
+public class ConnectionState {
+    private ConnectionStateKind kind;
+    private int reasonCode;
+    private String reasonMessage;
+    private int attempt;
+    private String error;
+
+    public ConnectionStateKind getKind() {
+        return kind;
+    }
+
+    /**
+     * Returns the close code, or `0` if the state is not `Disconnected` or the
+     * server did not send a close frame
+     */
+    public int getReasonCode() {
+        return reasonCode;
+    }
+
+    /**
+     * Returns the close reason, or `null` if the state is not `Disconnected` or
+     * the server did not send a close frame
+     */
+    public String getReasonMessage() {
+        return reasonMessage;
+    }
+
+    /**
+     * Returns the reconnect attempt starting from `1`, or `0` if the state is
+     * not `Reconnecting`
+     */
+    public int getAttempt() {
+        return attempt;
+    }
+
+    /**
+     * Returns the error that closed the context, or `null` if the state is not
+     * `Closed` or the context was closed normally
+     */
+    public String getError() {
+        return error;
+    }
+
+    @Override
+    public String toString() {
+        return "ConnectionState [kind=" + kind + ", reasonCode=" + reasonCode + ", reasonMessage=" + reasonMessage
+                + ", attempt=" + attempt + ", error=" + error + "]";
+    }
+
+}
@@ -0,0 +1,5 @@
+package com.longportwhale.trade;
+
+public interface ConnectionStateHandler {
+    void onConnectionState(ConnectionState state);
+}
@@ -0,0 +1,10 @@
+package com.longportwhale.trade;
+
+public enum ConnectionStateKind {
+    Connecting,
+    Connected,
+    Authenticated,
+    Disconnected,
+    Reconnecting,
+    Closed,
+}
@@ -29,6 +29,7 @@ public class PushOrderChanged {
     private BigDecimal lastShare;
     private BigDecimal lastPrice;
     private String remark;
+    private boolean recovered;
 
     public OrderSide getSide() {
         return side;
@@ -130,6 +131,14 @@ public class PushOrderChanged {
         return remark;
     }
 
+    /**
+     * Returns `true` if this event was synthesized from the order list fetched
+     * after a reconnect
+     */
+    public boolean isRecovered() {
+        return recovered;
+    }
+
     @Override
     public String toString() {
         return "PushOrderChanged [side=" + side + ", stockName=" + stockName + ", submittedQuantity="
@@ -140,7 +149,7 @@ public class PushOrderChanged {
                 + ", tag=" + tag + ", triggerStatus=" + triggerStatus + ", triggerAt=" + triggerAt + ", trailingAmount="
                 + trailingAmount + ", trailingPercent=" + trailingPercent + ", limitOffset=" + limitOffset
                 + ", accountNo=" + accountNo + ", lastShare=" + lastShare + ", lastPrice=" + lastPrice + ", remark="
-                + remark + "]";
+                + remark + ", recovered=" + recovered + "]";
     }
 
 }
@@ -0,0 +1,31 @@
+package com.longportwhale.trade;
+
+/**
+ * Push event not known by this version of the SDK
+ */
+public class PushRaw {
+    private String topic;
+    private String event;
+    private String data;
+
+    public String getTopic() {
+        return topic;
+    }
+
+    public String getEvent() {
+        return event;
+    }
+
+    /**
+     * Returns the event data in JSON
+     */
+    public String getData() {
+        return data;
+    }
+
+    @Override
+    public String toString() {
+        return "PushRaw [topic=" + topic + ", event=" + event + ", data=" + data + "]";
+    }
+
+}
@@ -0,0 +1,5 @@
+package com.longportwhale.trade;
+
+public interface RawPushHandler {
+    void onRawPush(PushRaw event);
+}
@@ -24,8 +24,17 @@ public class TradeContext implements AutoCloseable {
         });
     }
 
+    /**
+     * Gracefully close the trade context and release it
+     * <p>
+     * Unsubscribes all topics, waits for the pending requests and closes the
+     * connection before releasing the native object.
+     */
     @Override
     public void close() throws Exception {
+        AsyncCallback.executeTask((callback) -> {
+            SdkNative.tradeContextClose(this.raw, callback);
+        }).get();
         SdkNative.freeTradeContext(raw);
     }
 
@@ -39,6 +48,35 @@ public class TradeContext implements AutoCloseable {
         SdkNative.tradeContextSetOnOrderChanged(this.raw, handler);
     }
 
+    /**
+     * Set connection state callback, it will be called when the connection
+     * state changes.
+     * 
+     * @param handler A connection state handler
+     */
+    public void setOnConnectionState(ConnectionStateHandler handler) {
+        SdkNative.tradeContextSetOnConnectionState(this.raw, handler);
+    }
+
+    /**
+     * Set raw push callback, it will be called when receiving a push event not
+     * known by this version of the SDK.
+     * 
+     * @param handler A raw push handler
+     */
+    public void setOnRawPush(RawPushHandler handler) {
+        SdkNative.tradeContextSetOnRawPush(this.raw, handler);
+    }
+
+    /**
+     * Returns the current connection state
+     * 
+     * @return Connection state
+     */
+    public ConnectionState getState() {
+        return SdkNative.tradeContextState(this.raw);
+    }
+
     /**
      * Subscribe
      * 
@@ -85,4 +123,35 @@ public class TradeContext implements AutoCloseable {
             SdkNative.tradeContextUnsubscribe(this.raw, topics, callback);
         });
     }
+
+    /**
+     * Get account balance
+     * 
+     * <pre>
+     * {@code
+     * import com.longportwhale.*;
+     * import com.longportwhale.trade.*;
+     * 
+     * class Main {
+     *     public static void main(String[] args) throws Exception {
+     *         try (Config config = Config.fromEnv(); TradeContext ctx = TradeContext.create(config).get()) {
+     *             AccountBalance resp = ctx.getAccountBalance("L6VQEU00121996", "HKD").get();
+     *             System.out.println(resp);
+     *         }
+     *     }
+     * }
+     * }
+     * </pre>
+     * 
+     * @param accountNo Account no
+     * @param currency  Currency
+     * @return A Future representing the result of the operation
+     * @throws OpenApiException If an error occurs
+     */
+    public CompletableFuture<AccountBalance> getAccountBalance(String accountNo, String currency)
+            throws OpenApiException {
+        return AsyncCallback.executeTask((callback) -> {
+            SdkNative.tradeContextAccountBalance(this.raw, accountNo, currency, callback);
+        });
+    }
 }
@@ -83,9 +83,17 @@ pub extern "system" fn Java_com_longportwhale_SdkNative_init<'a>(
         longportwhale::trade::OrderStatus,
         longportwhale::trade::OrderTag,
         longportwhale::trade::TriggerStatus,
-        longportwhale::trade::TopicType
+        longportwhale::trade::TopicType,
+        crate::types::ConnectionStateKind
     );
 
     // classes
-    init_class_by_classloader!(env, longportwhale::trade::PushOrderChanged);
+    init_class_by_classloader!(
+        env,
+        longportwhale::trade::PushOrderChanged,
+        longportwhale::trade::CashInfo,
+        longportwhale::trade::AccountBalance,
+        crate::types::ConnectionState,
+        crate::types::PushRaw
+    );
 }
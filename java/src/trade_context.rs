@@ -2,8 +2,8 @@ use std::sync::Arc;
 
 use jni::{
     errors::Result,
-    objects::{GlobalRef, JClass, JObject, JValueOwned},
-    sys::jobjectArray,
+    objects::{GlobalRef, JClass, JObject, JString, JValueOwned},
+    sys::{jobject, jobjectArray},
     JNIEnv, JavaVM,
 };
 use longportwhale::{
@@ -16,12 +16,14 @@ use crate::{
     async_util,
     error::jni_result,
     init::TRADE_CONTEXT_CLASS,
-    types::{set_field, FromJValue, IntoJValue, ObjectArray},
+    types::{set_field, ConnectionState, FromJValue, IntoJValue, ObjectArray, PushRaw},
 };
 
 #[derive(Default)]
 struct Callbacks {
     order_changed: Option<GlobalRef>,
+    connection_state: Option<GlobalRef>,
+    raw_push: Option<GlobalRef>,
 }
 
 struct ContextObj {
@@ -44,6 +46,29 @@ fn send_push_event(jvm: &JavaVM, callbacks: &Callbacks, event: PushEvent) -> Res
                 )?;
             }
         }
+        PushEvent::Execution(_) => {}
+        PushEvent::ConnectionState(state) => {
+            if let Some(handler) = &callbacks.connection_state {
+                let state = ConnectionState::from(state).into_jvalue(&mut env)?;
+                env.call_method(
+                    handler,
+                    "onConnectionState",
+                    "(Lcom/longportwhale/trade/ConnectionState;)V",
+                    &[state.borrow()],
+                )?;
+            }
+        }
+        PushEvent::Unknown { topic, event, data } => {
+            if let Some(handler) = &callbacks.raw_push {
+                let event = PushRaw::new(topic, event, data).into_jvalue(&mut env)?;
+                env.call_method(
+                    handler,
+                    "onRawPush",
+                    "(Lcom/longportwhale/trade/PushRaw;)V",
+                    &[event.borrow()],
+                )?;
+            }
+        }
     }
 
     Ok(())
@@ -120,6 +145,72 @@ pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextSetOn
     })
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextSetOnConnectionState(
+    mut env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+    handler: JObject,
+) {
+    let context = &*(ctx as *const ContextObj);
+    jni_result(&mut env, (), |env| {
+        if !handler.is_null() {
+            context.callbacks.lock().connection_state = Some(env.new_global_ref(handler)?);
+        } else {
+            context.callbacks.lock().connection_state = None;
+        }
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextSetOnRawPush(
+    mut env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+    handler: JObject,
+) {
+    let context = &*(ctx as *const ContextObj);
+    jni_result(&mut env, (), |env| {
+        if !handler.is_null() {
+            context.callbacks.lock().raw_push = Some(env.new_global_ref(handler)?);
+        } else {
+            context.callbacks.lock().raw_push = None;
+        }
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextState(
+    mut env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+) -> jobject {
+    let context = &*(ctx as *const ContextObj);
+    jni_result(&mut env, std::ptr::null_mut(), |env| {
+        let state = ConnectionState::from(context.ctx.state()).into_jvalue(env)?;
+        Ok(state.l()?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextClose(
+    mut env: JNIEnv,
+    _class: JClass,
+    context: i64,
+    callback: JObject,
+) {
+    jni_result(&mut env, (), |env| {
+        let context = &*(context as *const ContextObj);
+        async_util::execute(env, callback, async move {
+            context.ctx.close().await;
+            Ok(())
+        })?;
+        Ok(())
+    })
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextSubscribe(
     mut env: JNIEnv,
@@ -157,3 +248,23 @@ pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextUnsub
         Ok(())
     })
 }
+
+#[no_mangle]
+pub unsafe extern "system" fn Java_com_longportwhale_SdkNative_tradeContextAccountBalance(
+    mut env: JNIEnv,
+    _class: JClass,
+    context: i64,
+    account_no: JString,
+    currency: JString,
+    callback: JObject,
+) {
+    jni_result(&mut env, (), |env| {
+        let context = &*(context as *const ContextObj);
+        let account_no = String::from_jvalue(env, account_no.into())?;
+        let currency = String::from_jvalue(env, currency.into())?;
+        async_util::execute(env, callback, async move {
+            Ok(context.ctx.account_balance(account_no, currency).await?)
+        })?;
+        Ok(())
+    })
+}
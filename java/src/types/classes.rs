@@ -30,6 +30,38 @@ impl_java_class!(
         account_no,
         last_share,
         last_price,
-        remark
+        remark,
+        recovered
+    ]
+);
+
+impl_java_class!(
+    "com/longportwhale/trade/CashInfo",
+    longportwhale::trade::CashInfo,
+    [
+        withdraw_cash,
+        available_cash,
+        frozen_cash,
+        settling_cash,
+        currency
+    ]
+);
+
+impl_java_class!(
+    "com/longportwhale/trade/AccountBalance",
+    longportwhale::trade::AccountBalance,
+    [
+        total_cash,
+        max_finance_amount,
+        remaining_finance_amount,
+        risk_level,
+        margin_call,
+        currency,
+        #[java(objarray)]
+        cash_infos,
+        net_assets,
+        init_margin,
+        maintenance_margin,
+        buy_power
     ]
 );
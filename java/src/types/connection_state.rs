@@ -0,0 +1,77 @@
+use std::borrow::Borrow;
+
+use longportwhale_java_macros::{impl_java_class, impl_java_enum};
+
+pub(crate) enum ConnectionStateKind {
+    Connecting,
+    Connected,
+    Authenticated,
+    Disconnected,
+    Reconnecting,
+    Closed,
+}
+
+impl_java_enum!(
+    "com/longportwhale/trade/ConnectionStateKind",
+    ConnectionStateKind,
+    [
+        Connecting,
+        Connected,
+        Authenticated,
+        Disconnected,
+        Reconnecting,
+        Closed
+    ]
+);
+
+/// Flattened [`longportwhale::trade::ConnectionState`], the close code and
+/// the attempt are `0` if they do not apply to the state.
+pub(crate) struct ConnectionState {
+    kind: ConnectionStateKind,
+    reason_code: i32,
+    reason_message: Option<String>,
+    attempt: i32,
+    error: Option<String>,
+}
+
+impl_java_class!(
+    "com/longportwhale/trade/ConnectionState",
+    ConnectionState,
+    [kind, reason_code, reason_message, attempt, error]
+);
+
+impl From<longportwhale::trade::ConnectionState> for ConnectionState {
+    fn from(state: longportwhale::trade::ConnectionState) -> Self {
+        use longportwhale::trade::ConnectionState as State;
+
+        let (kind, reason, attempt, error) = match state {
+            State::Connecting => (ConnectionStateKind::Connecting, None, 0, None),
+            State::Connected => (ConnectionStateKind::Connected, None, 0, None),
+            State::Authenticated => (ConnectionStateKind::Authenticated, None, 0, None),
+            State::Disconnected { reason } => (ConnectionStateKind::Disconnected, reason, 0, None),
+            State::Reconnecting { attempt } => (
+                ConnectionStateKind::Reconnecting,
+                None,
+                attempt as i32,
+                None,
+            ),
+            State::Closed { error } => (
+                ConnectionStateKind::Closed,
+                None,
+                0,
+                error.map(|err| err.to_string()),
+            ),
+        };
+
+        Self {
+            kind,
+            reason_code: reason
+                .as_ref()
+                .map(|reason| u16::from(reason.code) as i32)
+                .unwrap_or_default(),
+            reason_message: reason.map(|reason| reason.message),
+            attempt,
+            error,
+        }
+    }
+}
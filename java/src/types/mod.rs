@@ -1,4 +1,5 @@
 mod classes;
+mod connection_state;
 mod datetime;
 mod decimal;
 mod enum_types;
@@ -6,6 +7,7 @@ mod object_array;
 mod optional;
 mod primary_array;
 mod primary_types;
+mod push_raw;
 mod string;
 
 use std::borrow::Cow;
@@ -17,7 +19,11 @@ use jni::{
     JNIEnv,
 };
 
-pub(crate) use self::object_array::ObjectArray;
+pub(crate) use self::{
+    connection_state::{ConnectionState, ConnectionStateKind},
+    object_array::ObjectArray,
+    push_raw::PushRaw,
+};
 
 pub(crate) trait ClassLoader {
     fn init(env: &mut JNIEnv);
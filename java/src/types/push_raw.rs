@@ -0,0 +1,26 @@
+use std::borrow::Borrow;
+
+use longportwhale_java_macros::impl_java_class;
+
+/// [`longportwhale::trade::PushEvent::Unknown`], the data is in JSON
+pub(crate) struct PushRaw {
+    topic: String,
+    event: String,
+    data: String,
+}
+
+impl_java_class!(
+    "com/longportwhale/trade/PushRaw",
+    PushRaw,
+    [topic, event, data]
+);
+
+impl PushRaw {
+    pub(crate) fn new(topic: String, event: String, data: serde_json::Value) -> Self {
+        Self {
+            topic,
+            event,
+            data: data.to_string(),
+        }
+    }
+}
@@ -3,6 +3,7 @@ use jni::{
     objects::{JString, JValueOwned},
     JNIEnv,
 };
+use longportwhale::Symbol;
 
 use crate::{
     init::STRING_CLASS,
@@ -40,3 +41,15 @@ impl IntoJValue for String {
         env.new_string(self).map(JValueOwned::from)
     }
 }
+
+impl JSignature for Symbol {
+    fn signature() -> std::borrow::Cow<'static, str> {
+        String::signature()
+    }
+}
+
+impl IntoJValue for Symbol {
+    fn into_jvalue<'a>(self, env: &mut JNIEnv<'a>) -> Result<JValueOwned<'a>> {
+        self.to_string().into_jvalue(env)
+    }
+}
@@ -139,6 +139,21 @@ export const enum TriggerStatus {
   /** Released */
   Released = 3
 }
+/** Connection state kind */
+export const enum ConnectionStateKind {
+  /** Connecting to the server */
+  Connecting = 0,
+  /** Connected, but not yet authenticated */
+  Connected = 1,
+  /** Authenticated, push events will be received */
+  Authenticated = 2,
+  /** Disconnected from the server */
+  Disconnected = 3,
+  /** Reconnecting to the server */
+  Reconnecting = 4,
+  /** Closed, will not reconnect */
+  Closed = 5
+}
 export const enum Market {
   /** Unknown */
   Unknown = 0,
@@ -370,6 +385,25 @@ export class TradeContext {
    * will call back to this function.
    */
   setOnOrderChanged(callback: (err: null | Error, event: PushOrderChanged) => void): void
+  /**
+   * Set connection state callback, it will be called when the connection
+   * state changes.
+   */
+  setOnConnectionState(callback: (err: null | Error, state: ConnectionState) => void): void
+  /**
+   * Set raw push callback, it will be called when receiving a push event
+   * not known by this version of the SDK.
+   */
+  setOnRawPush(callback: (err: null | Error, event: PushRaw) => void): void
+  /** Returns the current connection state */
+  state(): ConnectionState
+  /**
+   * Gracefully close the trade context
+   *
+   * Unsubscribes all topics, waits for the pending requests and closes the
+   * connection. The requests sent after this will fail.
+   */
+  close(): Promise<void>
   /**
    * Subscribe
    *
@@ -395,6 +429,21 @@ export class TradeContext {
   subscribe(topics: Array<TopicType>): Promise<void>
   /** Unsubscribe */
   unsubscribe(topics: Array<TopicType>): Promise<void>
+  /**
+   * Get account balance
+   *
+   * #### Example
+   *
+   * ```javascript
+   * const { Config, TradeContext } = require("longportwhale");
+   *
+   * let config = Config.fromEnv();
+   * let ctx = await TradeContext.new(config);
+   * let resp = await ctx.accountBalance("L6VQEU00121996", "HKD");
+   * console.log(resp.toString());
+   * ```
+   */
+  accountBalance(accountNo: string, currency: string): Promise<AccountBalance>
 }
 /** Order changed message */
 export class PushOrderChanged {
@@ -449,4 +498,73 @@ export class PushOrderChanged {
   get lastPrice(): Decimal | null
   /** Remark message */
   get remark(): string
+  /**
+   * `true` if this event was synthesized from the order list fetched after
+   * a reconnect
+   */
+  get recovered(): boolean
+}
+/** Account balance */
+export class AccountBalance {
+  toString(): string
+  /** Total cash */
+  get totalCash(): Decimal
+  /** Maximum financing amount */
+  get maxFinanceAmount(): Decimal
+  /** Remaining financing amount */
+  get remainingFinanceAmount(): Decimal
+  /** Risk control level */
+  get riskLevel(): number
+  /** Margin call */
+  get marginCall(): Decimal
+  /** Currency */
+  get currency(): string
+  /** Cash details */
+  get cashInfos(): Array<CashInfo>
+  /** Net assets */
+  get netAssets(): Decimal
+  /** Initial margin */
+  get initMargin(): Decimal
+  /** Maintenance margin */
+  get maintenanceMargin(): Decimal
+  /** Buy power */
+  get buyPower(): Decimal
+}
+/** Cash info */
+export class CashInfo {
+  toString(): string
+  /** Withdraw cash */
+  get withdrawCash(): Decimal
+  /** Available cash */
+  get availableCash(): Decimal
+  /** Frozen cash */
+  get frozenCash(): Decimal
+  /** Cash to be settled */
+  get settlingCash(): Decimal
+  /** Currency */
+  get currency(): string
+}
+/** Connection state */
+export class ConnectionState {
+  toString(): string
+  /** State kind */
+  get kind(): ConnectionStateKind
+  /** Close code (only for `Disconnected`) */
+  get reasonCode(): number | null
+  /** Close reason (only for `Disconnected`) */
+  get reasonMessage(): string | null
+  /** Reconnect attempt, starting from `1` (only for `Reconnecting`) */
+  get attempt(): number | null
+  /** The error that closed the context (only for `Closed`) */
+  get error(): string | null
+}
+/** Push event not known by this version of the SDK */
+export class PushRaw {
+  toString(): string
+  /** Topic of the notification */
+  get topic(): string
+  /** Event name */
+  get event(): string
+  /** Event data in JSON */
+  get data(): string
 }
@@ -310,7 +310,7 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { Config, Decimal, HttpClient, NaiveDate, Time, NaiveDatetime, TradeContext, TopicType, OrderStatus, OrderSide, OrderType, OrderTag, TriggerStatus, PushOrderChanged, Market, Language } = nativeBinding
+const { Config, Decimal, HttpClient, NaiveDate, Time, NaiveDatetime, TradeContext, TopicType, OrderStatus, OrderSide, OrderType, OrderTag, TriggerStatus, PushOrderChanged, AccountBalance, CashInfo, ConnectionStateKind, ConnectionState, PushRaw, Market, Language } = nativeBinding
 
 module.exports.Config = Config
 module.exports.Decimal = Decimal
@@ -326,5 +326,10 @@ module.exports.OrderType = OrderType
 module.exports.OrderTag = OrderTag
 module.exports.TriggerStatus = TriggerStatus
 module.exports.PushOrderChanged = PushOrderChanged
+module.exports.AccountBalance = AccountBalance
+module.exports.CashInfo = CashInfo
+module.exports.ConnectionStateKind = ConnectionStateKind
+module.exports.ConnectionState = ConnectionState
+module.exports.PushRaw = PushRaw
 module.exports.Market = Market
 module.exports.Language = Language
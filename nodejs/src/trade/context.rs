@@ -7,13 +7,15 @@ use parking_lot::Mutex;
 use crate::{
     config::Config,
     error::ErrorNewType,
-    trade::types::{PushOrderChanged, TopicType},
+    trade::types::{AccountBalance, ConnectionState, PushOrderChanged, PushRaw, TopicType},
     utils::JsCallback,
 };
 
 #[derive(Default)]
 struct Callbacks {
     order_changed: Option<JsCallback<PushOrderChanged>>,
+    connection_state: Option<JsCallback<ConnectionState>>,
+    raw_push: Option<JsCallback<PushRaw>>,
 }
 
 /// Trade context
@@ -50,6 +52,21 @@ impl TradeContext {
                                 }
                             }
                         }
+                        PushEvent::Execution(_) => {}
+                        PushEvent::ConnectionState(state) => {
+                            if let Some(callback) = &callbacks.connection_state {
+                                callback
+                                    .call(Ok(state.into()), ThreadsafeFunctionCallMode::Blocking);
+                            }
+                        }
+                        PushEvent::Unknown { topic, event, data } => {
+                            if let Some(callback) = &callbacks.raw_push {
+                                callback.call(
+                                    Ok(PushRaw::new(topic, event, data)),
+                                    ThreadsafeFunctionCallMode::Blocking,
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -67,6 +84,40 @@ impl TradeContext {
         Ok(())
     }
 
+    /// Set connection state callback, it will be called when the connection
+    /// state changes.
+    #[napi(ts_args_type = "callback: (err: null | Error, state: ConnectionState) => void")]
+    pub fn set_on_connection_state(&self, callback: JsFunction) -> Result<()> {
+        self.callbacks.lock().connection_state =
+            Some(callback.create_threadsafe_function(32, |ctx| Ok(vec![ctx.value]))?);
+        Ok(())
+    }
+
+    /// Set raw push callback, it will be called when receiving a push event
+    /// not known by this version of the SDK.
+    #[napi(ts_args_type = "callback: (err: null | Error, event: PushRaw) => void")]
+    pub fn set_on_raw_push(&self, callback: JsFunction) -> Result<()> {
+        self.callbacks.lock().raw_push =
+            Some(callback.create_threadsafe_function(32, |ctx| Ok(vec![ctx.value]))?);
+        Ok(())
+    }
+
+    /// Returns the current connection state
+    #[napi]
+    pub fn state(&self) -> ConnectionState {
+        self.ctx.state().into()
+    }
+
+    /// Gracefully close the trade context
+    ///
+    /// Unsubscribes all topics, waits for the pending requests and closes the
+    /// connection. The requests sent after this will fail.
+    #[napi]
+    pub async fn close(&self) -> Result<()> {
+        self.ctx.close().await;
+        Ok(())
+    }
+
     /// Subscribe
     ///
     /// #### Example
@@ -105,4 +156,29 @@ impl TradeContext {
             .map_err(ErrorNewType)?;
         Ok(())
     }
+
+    /// Get account balance
+    ///
+    /// #### Example
+    ///
+    /// ```javascript
+    /// const { Config, TradeContext } = require("longportwhale");
+    ///
+    /// let config = Config.fromEnv();
+    /// let ctx = await TradeContext.new(config);
+    /// let resp = await ctx.accountBalance("L6VQEU00121996", "HKD");
+    /// console.log(resp.toString());
+    /// ```
+    #[napi]
+    pub async fn account_balance(
+        &self,
+        account_no: String,
+        currency: String,
+    ) -> Result<AccountBalance> {
+        self.ctx
+            .account_balance(account_no, currency)
+            .await
+            .map_err(ErrorNewType)?
+            .try_into()
+    }
 }
@@ -209,4 +209,200 @@ pub struct PushOrderChanged {
     last_price: Option<Decimal>,
     /// Remark message
     remark: String,
+    /// `true` if this event was synthesized from the order list fetched after
+    /// a reconnect
+    recovered: bool,
+}
+
+/// Account balance
+#[napi_derive::napi]
+#[derive(Debug, JsObject)]
+#[js(remote = "longportwhale::trade::AccountBalance")]
+pub struct AccountBalance {
+    /// Total cash
+    total_cash: Decimal,
+    /// Maximum financing amount
+    max_finance_amount: Decimal,
+    /// Remaining financing amount
+    remaining_finance_amount: Decimal,
+    /// Risk control level
+    risk_level: i32,
+    /// Margin call
+    margin_call: Decimal,
+    /// Currency
+    currency: String,
+    /// Cash details
+    #[js(array)]
+    cash_infos: Vec<CashInfo>,
+    /// Net assets
+    net_assets: Decimal,
+    /// Initial margin
+    init_margin: Decimal,
+    /// Maintenance margin
+    maintenance_margin: Decimal,
+    /// Buy power
+    buy_power: Decimal,
+}
+
+/// Cash info
+#[napi_derive::napi]
+#[derive(Debug, JsObject, Clone)]
+#[js(remote = "longportwhale::trade::CashInfo")]
+pub struct CashInfo {
+    /// Withdraw cash
+    withdraw_cash: Decimal,
+    /// Available cash
+    available_cash: Decimal,
+    /// Frozen cash
+    frozen_cash: Decimal,
+    /// Cash to be settled
+    settling_cash: Decimal,
+    /// Currency
+    currency: String,
+}
+
+/// Connection state kind
+#[napi_derive::napi]
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub enum ConnectionStateKind {
+    /// Connecting to the server
+    Connecting,
+    /// Connected, but not yet authenticated
+    Connected,
+    /// Authenticated, push events will be received
+    Authenticated,
+    /// Disconnected from the server
+    Disconnected,
+    /// Reconnecting to the server
+    Reconnecting,
+    /// Closed, will not reconnect
+    Closed,
+}
+
+/// Connection state
+#[napi_derive::napi]
+#[derive(Debug, Clone)]
+pub struct ConnectionState {
+    kind: ConnectionStateKind,
+    reason_code: Option<u16>,
+    reason_message: Option<String>,
+    attempt: Option<u32>,
+    error: Option<String>,
+}
+
+impl From<longportwhale::trade::ConnectionState> for ConnectionState {
+    fn from(state: longportwhale::trade::ConnectionState) -> Self {
+        use longportwhale::trade::ConnectionState as State;
+
+        let (kind, reason, attempt, error) = match state {
+            State::Connecting => (ConnectionStateKind::Connecting, None, None, None),
+            State::Connected => (ConnectionStateKind::Connected, None, None, None),
+            State::Authenticated => (ConnectionStateKind::Authenticated, None, None, None),
+            State::Disconnected { reason } => {
+                (ConnectionStateKind::Disconnected, reason, None, None)
+            }
+            State::Reconnecting { attempt } => {
+                (ConnectionStateKind::Reconnecting, None, Some(attempt), None)
+            }
+            State::Closed { error } => (
+                ConnectionStateKind::Closed,
+                None,
+                None,
+                error.map(|err| err.to_string()),
+            ),
+        };
+
+        Self {
+            kind,
+            reason_code: reason.as_ref().map(|reason| reason.code.into()),
+            reason_message: reason.map(|reason| reason.message),
+            attempt,
+            error,
+        }
+    }
+}
+
+#[napi_derive::napi]
+impl ConnectionState {
+    #[napi]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// State kind
+    #[napi(getter)]
+    pub fn kind(&self) -> ConnectionStateKind {
+        self.kind
+    }
+
+    /// Close code (only for `Disconnected`)
+    #[napi(getter)]
+    pub fn reason_code(&self) -> Option<u16> {
+        self.reason_code
+    }
+
+    /// Close reason (only for `Disconnected`)
+    #[napi(getter)]
+    pub fn reason_message(&self) -> Option<String> {
+        self.reason_message.clone()
+    }
+
+    /// Reconnect attempt, starting from `1` (only for `Reconnecting`)
+    #[napi(getter)]
+    pub fn attempt(&self) -> Option<u32> {
+        self.attempt
+    }
+
+    /// The error that closed the context (only for `Closed`)
+    #[napi(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Push event not known by this version of the SDK
+#[napi_derive::napi]
+#[derive(Debug, Clone)]
+pub struct PushRaw {
+    topic: String,
+    event: String,
+    data: String,
+}
+
+impl PushRaw {
+    pub(crate) fn new(topic: String, event: String, data: serde_json::Value) -> Self {
+        Self {
+            topic,
+            event,
+            data: data.to_string(),
+        }
+    }
+}
+
+#[napi_derive::napi]
+impl PushRaw {
+    #[napi]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// Topic of the notification
+    #[napi(getter)]
+    pub fn topic(&self) -> String {
+        self.topic.clone()
+    }
+
+    /// Event name
+    #[napi(getter)]
+    pub fn event(&self) -> String {
+        self.event.clone()
+    }
+
+    /// Event data in JSON
+    #[napi(getter)]
+    pub fn data(&self) -> String {
+        self.data.clone()
+    }
 }
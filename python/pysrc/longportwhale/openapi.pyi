@@ -597,6 +597,103 @@ class PushOrderChanged:
     Remark message
     """
 
+    recovered: bool
+    """
+    `true` if this event was synthesized from the order list fetched after a reconnect
+    """
+
+
+class CashInfo:
+    """
+    Cash info
+    """
+
+    withdraw_cash: Decimal
+    """
+    Withdraw cash
+    """
+
+    available_cash: Decimal
+    """
+    Available cash
+    """
+
+    frozen_cash: Decimal
+    """
+    Frozen cash
+    """
+
+    settling_cash: Decimal
+    """
+    Cash to be settled
+    """
+
+    currency: str
+    """
+    Currency
+    """
+
+
+class AccountBalance:
+    """
+    Account balance
+    """
+
+    total_cash: Decimal
+    """
+    Total cash
+    """
+
+    max_finance_amount: Decimal
+    """
+    Maximum financing amount
+    """
+
+    remaining_finance_amount: Decimal
+    """
+    Remaining financing amount
+    """
+
+    risk_level: int
+    """
+    Risk control level
+    """
+
+    margin_call: Decimal
+    """
+    Margin call
+    """
+
+    currency: str
+    """
+    Currency
+    """
+
+    cash_infos: List[CashInfo]
+    """
+    Cash details
+    """
+
+    net_assets: Decimal
+    """
+    Net assets
+    """
+
+    init_margin: Decimal
+    """
+    Initial margin
+    """
+
+    maintenance_margin: Decimal
+    """
+    Maintenance margin
+    """
+
+    buy_power: Decimal
+    """
+    Buy power
+    """
+
 
 class TopicType:
     """
@@ -610,6 +707,100 @@ class TopicType:
         ...
 
 
+class ConnectionStateKind:
+    """
+    Connection state kind
+    """
+
+    class Connecting(ConnectionStateKind):
+        """
+        Connecting to the server
+        """
+        ...
+
+    class Connected(ConnectionStateKind):
+        """
+        Connected, but not yet authenticated
+        """
+        ...
+
+    class Authenticated(ConnectionStateKind):
+        """
+        Authenticated, push events will be received
+        """
+        ...
+
+    class Disconnected(ConnectionStateKind):
+        """
+        Disconnected from the server
+        """
+        ...
+
+    class Reconnecting(ConnectionStateKind):
+        """
+        Reconnecting to the server
+        """
+        ...
+
+    class Closed(ConnectionStateKind):
+        """
+        Closed, will not reconnect
+        """
+        ...
+
+
+class ConnectionState:
+    """
+    Connection state
+    """
+
+    kind: Type[ConnectionStateKind]
+    """
+    State kind
+    """
+
+    reason_code: Optional[int]
+    """
+    Close code (only for `Disconnected`)
+    """
+
+    reason_message: Optional[str]
+    """
+    Close reason (only for `Disconnected`)
+    """
+
+    attempt: Optional[int]
+    """
+    Reconnect attempt, starting from `1` (only for `Reconnecting`)
+    """
+
+    error: Optional[str]
+    """
+    The error that closed the context (only for `Closed`)
+    """
+
+
+class PushRaw:
+    """
+    Push event not known by this version of the SDK
+    """
+
+    topic: str
+    """
+    Topic of the notification
+    """
+
+    event: str
+    """
+    Event name
+    """
+
+    data: str
+    """
+    Event data in JSON
+    """
+
+
 class TradeContext:
     """
     Trade context
@@ -625,6 +816,29 @@ class TradeContext:
         Set order changed callback, after receiving the order changed event, it will call back to this function.
         """
 
+    def set_on_connection_state(self, callback: Callable[[ConnectionState], None]) -> None:
+        """
+        Set connection state callback, it will be called when the connection state changes.
+        """
+
+    def set_on_raw_push(self, callback: Callable[[PushRaw], None]) -> None:
+        """
+        Set raw push callback, it will be called when receiving a push event not known by this version of the SDK.
+        """
+
+    def state(self) -> ConnectionState:
+        """
+        Returns the current connection state
+        """
+
+    def close(self) -> None:
+        """
+        Gracefully close the trade context
+
+        Unsubscribes all topics, waits for the pending requests and closes the
+        connection. The requests sent after this will fail.
+        """
+
     def subscribe(self, topics: List[Type[TopicType]]) -> None:
         """
         Subscribe
@@ -659,3 +873,25 @@ class TradeContext:
             topics: Topic list
         """
 
+    def account_balance(self, account_no: str, currency: str) -> AccountBalance:
+        """
+        Get account balance
+
+        Args:
+            account_no: Account no
+            currency: Currency
+
+        Returns:
+            Account balance
+
+        Examples:
+            ::
+
+                from longportwhale.openapi import TradeContext, Config
+
+                config = Config.from_env()
+                ctx = TradeContext(config)
+
+                resp = ctx.account_balance("L6VQEU00121996", "HKD")
+                print(resp)
+        """
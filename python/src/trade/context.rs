@@ -7,12 +7,17 @@ use pyo3::{pyclass, pymethods, PyObject, PyResult, Python};
 use crate::{
     config::Config,
     error::ErrorNewType,
-    trade::{push::handle_push_event, types::TopicType},
+    trade::{
+        push::handle_push_event,
+        types::{AccountBalance, ConnectionState, TopicType},
+    },
 };
 
 #[derive(Debug, Default)]
 pub(crate) struct Callbacks {
     pub(crate) order_changed: Option<PyObject>,
+    pub(crate) connection_state: Option<PyObject>,
+    pub(crate) raw_push: Option<PyObject>,
 }
 
 #[pyclass]
@@ -46,6 +51,38 @@ impl TradeContext {
         }
     }
 
+    /// Set connection state callback, it will be called when the connection
+    /// state changes.
+    fn set_on_connection_state(&self, py: Python<'_>, callback: PyObject) {
+        if callback.is_none(py) {
+            self.callbacks.lock().connection_state = None;
+        } else {
+            self.callbacks.lock().connection_state = Some(callback);
+        }
+    }
+
+    /// Set raw push callback, it will be called when receiving a push event
+    /// not known by this version of the SDK.
+    fn set_on_raw_push(&self, py: Python<'_>, callback: PyObject) {
+        if callback.is_none(py) {
+            self.callbacks.lock().raw_push = None;
+        } else {
+            self.callbacks.lock().raw_push = Some(callback);
+        }
+    }
+
+    /// Returns the current connection state
+    fn state(&self) -> PyResult<ConnectionState> {
+        Ok(self.ctx.state().map_err(ErrorNewType)?.into())
+    }
+
+    /// Gracefully close the trade context
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        py.allow_threads(|| self.ctx.close())
+            .map_err(ErrorNewType)?;
+        Ok(())
+    }
+
     /// Subscribe
     fn subscribe(&self, topics: Vec<TopicType>) -> PyResult<()> {
         self.ctx
@@ -61,4 +98,12 @@ impl TradeContext {
             .map_err(ErrorNewType)?;
         Ok(())
     }
+
+    /// Get account balance
+    fn account_balance(&self, account_no: String, currency: String) -> PyResult<AccountBalance> {
+        self.ctx
+            .account_balance(account_no, currency)
+            .map_err(ErrorNewType)?
+            .try_into()
+    }
 }
@@ -12,6 +12,11 @@ pub(crate) fn register_types(parent: &PyModule) -> PyResult<()> {
     parent.add_class::<types::OrderTag>()?;
     parent.add_class::<types::TriggerStatus>()?;
     parent.add_class::<types::PushOrderChanged>()?;
+    parent.add_class::<types::PushRaw>()?;
+    parent.add_class::<types::ConnectionStateKind>()?;
+    parent.add_class::<types::ConnectionState>()?;
+    parent.add_class::<types::AccountBalance>()?;
+    parent.add_class::<types::CashInfo>()?;
     parent.add_class::<context::TradeContext>()?;
     Ok(())
 }
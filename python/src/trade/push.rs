@@ -1,4 +1,4 @@
-use longportwhale::trade::{PushEvent, PushOrderChanged};
+use longportwhale::trade::{ConnectionState, PushEvent, PushOrderChanged};
 use pyo3::Python;
 
 use crate::trade::context::Callbacks;
@@ -6,6 +6,25 @@ use crate::trade::context::Callbacks;
 pub(crate) fn handle_push_event(callbacks: &Callbacks, event: PushEvent) {
     match event {
         PushEvent::OrderChanged(order_changed) => handle_order_changed(callbacks, order_changed),
+        PushEvent::Execution(_) => {}
+        PushEvent::ConnectionState(state) => handle_connection_state(callbacks, state),
+        PushEvent::Unknown { topic, event, data } => handle_raw_push(callbacks, topic, event, data),
+    }
+}
+
+fn handle_raw_push(callbacks: &Callbacks, topic: String, event: String, data: serde_json::Value) {
+    if let Some(callback) = &callbacks.raw_push {
+        let _ = Python::with_gil(|py| {
+            callback.call1(py, (crate::trade::types::PushRaw::new(topic, event, data),))
+        });
+    }
+}
+
+fn handle_connection_state(callbacks: &Callbacks, state: ConnectionState) {
+    if let Some(callback) = &callbacks.connection_state {
+        let _ = Python::with_gil(|py| {
+            callback.call1(py, (crate::trade::types::ConnectionState::from(state),))
+        });
     }
 }
 
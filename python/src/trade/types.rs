@@ -1,5 +1,5 @@
 use longportwhale_python_macros::{PyEnum, PyObject};
-use pyo3::pyclass;
+use pyo3::{pyclass, pymethods};
 
 use crate::{decimal::PyDecimal, time::PyOffsetDateTimeWrapper};
 
@@ -207,4 +207,204 @@ pub(crate) struct PushOrderChanged {
     last_price: Option<PyDecimal>,
     /// Remark message
     remark: String,
+    /// `true` if this event was synthesized from the order list fetched after
+    /// a reconnect
+    recovered: bool,
+}
+
+/// Account balance
+#[pyclass]
+#[derive(Debug, PyObject)]
+#[py(remote = "longportwhale::trade::AccountBalance")]
+pub(crate) struct AccountBalance {
+    /// Total cash
+    total_cash: PyDecimal,
+    /// Maximum financing amount
+    max_finance_amount: PyDecimal,
+    /// Remaining financing amount
+    remaining_finance_amount: PyDecimal,
+    /// Risk control level
+    risk_level: i32,
+    /// Margin call
+    margin_call: PyDecimal,
+    /// Currency
+    currency: String,
+    /// Cash details
+    #[py(array)]
+    cash_infos: Vec<CashInfo>,
+    /// Net assets
+    net_assets: PyDecimal,
+    /// Initial margin
+    init_margin: PyDecimal,
+    /// Maintenance margin
+    maintenance_margin: PyDecimal,
+    /// Buy power
+    buy_power: PyDecimal,
+}
+
+/// Cash info
+#[pyclass]
+#[derive(Debug, PyObject, Clone)]
+#[py(remote = "longportwhale::trade::CashInfo")]
+pub(crate) struct CashInfo {
+    /// Withdraw cash
+    withdraw_cash: PyDecimal,
+    /// Available cash
+    available_cash: PyDecimal,
+    /// Frozen cash
+    frozen_cash: PyDecimal,
+    /// Cash to be settled
+    settling_cash: PyDecimal,
+    /// Currency
+    currency: String,
+}
+
+/// Connection state kind
+#[pyclass]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub(crate) enum ConnectionStateKind {
+    /// Connecting to the server
+    Connecting,
+    /// Connected, but not yet authenticated
+    Connected,
+    /// Authenticated, push events will be received
+    Authenticated,
+    /// Disconnected from the server
+    Disconnected,
+    /// Reconnecting to the server
+    Reconnecting,
+    /// Closed, will not reconnect
+    Closed,
+}
+
+/// Connection state
+#[pyclass]
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionState {
+    /// State kind
+    kind: ConnectionStateKind,
+    /// Close code (only for `Disconnected`)
+    reason_code: Option<u16>,
+    /// Close reason (only for `Disconnected`)
+    reason_message: Option<String>,
+    /// Reconnect attempt, starting from `1` (only for `Reconnecting`)
+    attempt: Option<u32>,
+    /// The error that closed the context (only for `Closed`)
+    error: Option<String>,
+}
+
+impl From<longportwhale::trade::ConnectionState> for ConnectionState {
+    fn from(state: longportwhale::trade::ConnectionState) -> Self {
+        use longportwhale::trade::ConnectionState as State;
+
+        let (kind, reason, attempt, error) = match state {
+            State::Connecting => (ConnectionStateKind::Connecting, None, None, None),
+            State::Connected => (ConnectionStateKind::Connected, None, None, None),
+            State::Authenticated => (ConnectionStateKind::Authenticated, None, None, None),
+            State::Disconnected { reason } => {
+                (ConnectionStateKind::Disconnected, reason, None, None)
+            }
+            State::Reconnecting { attempt } => {
+                (ConnectionStateKind::Reconnecting, None, Some(attempt), None)
+            }
+            State::Closed { error } => (
+                ConnectionStateKind::Closed,
+                None,
+                None,
+                error.map(|err| err.to_string()),
+            ),
+        };
+
+        Self {
+            kind,
+            reason_code: reason.as_ref().map(|reason| reason.code.into()),
+            reason_message: reason.map(|reason| reason.message),
+            attempt,
+            error,
+        }
+    }
+}
+
+#[pymethods]
+impl ConnectionState {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    #[getter]
+    fn kind(&self) -> ConnectionStateKind {
+        self.kind
+    }
+
+    #[getter]
+    fn reason_code(&self) -> Option<u16> {
+        self.reason_code
+    }
+
+    #[getter]
+    fn reason_message(&self) -> Option<String> {
+        self.reason_message.clone()
+    }
+
+    #[getter]
+    fn attempt(&self) -> Option<u32> {
+        self.attempt
+    }
+
+    #[getter]
+    fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Push event not known by this version of the SDK
+#[pyclass]
+#[derive(Debug, Clone)]
+pub(crate) struct PushRaw {
+    /// Topic of the notification
+    topic: String,
+    /// Event name
+    event: String,
+    /// Event data in JSON
+    data: String,
+}
+
+impl PushRaw {
+    pub(crate) fn new(topic: String, event: String, data: serde_json::Value) -> Self {
+        Self {
+            topic,
+            event,
+            data: data.to_string(),
+        }
+    }
+}
+
+#[pymethods]
+impl PushRaw {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    #[getter]
+    fn topic(&self) -> String {
+        self.topic.clone()
+    }
+
+    #[getter]
+    fn event(&self) -> String {
+        self.event.clone()
+    }
+
+    #[getter]
+    fn data(&self) -> String {
+        self.data.clone()
+    }
 }
@@ -0,0 +1,44 @@
+use std::hint::black_box;
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, Criterion};
+use longport_proto::trade::Notification;
+use longportwhale::trade::bench_parse_push;
+use prost::Message;
+
+/// Command code of the push notifications
+const PUSH_NOTIFICATION: u8 = 18;
+
+fn notification(data: serde_json::Value) -> Bytes {
+    Notification {
+        topic: "private".to_string(),
+        data: serde_json::to_vec(&data).unwrap(),
+        ..Default::default()
+    }
+    .encode_to_vec()
+    .into()
+}
+
+fn order_changed() -> Bytes {
+    let data: serde_json::Value =
+        serde_json::from_str(include_str!("../src/trade/fixtures/order_changed.json")).unwrap();
+    notification(serde_json::json!({ "event": "order_changed_lb", "data": data }))
+}
+
+fn parse(c: &mut Criterion) {
+    let body = order_changed();
+    c.bench_function("parse_order_changed", |b| {
+        b.iter(|| bench_parse_push(PUSH_NOTIFICATION, black_box(body.clone())).unwrap())
+    });
+
+    let body = notification(serde_json::json!({
+        "event": "unknown",
+        "data": { "symbol": "700.HK", "quantity": "100" }
+    }));
+    c.bench_function("parse_unknown", |b| {
+        b.iter(|| bench_parse_push(PUSH_NOTIFICATION, black_box(body.clone())).unwrap())
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
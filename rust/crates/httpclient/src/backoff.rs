@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+const INITIAL_DELAY: Duration = Duration::from_millis(100);
+const FACTOR: f32 = 2.0;
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+struct State {
+    delay: Duration,
+    until: Option<Instant>,
+}
+
+/// Backoff state for `429 Too Many Requests` responses
+///
+/// It is shared by all clones of an [`HttpClient`](crate::HttpClient), so
+/// when one request is rate limited, the other requests sent concurrently
+/// through the same client wait for the same window instead of each one
+/// hammering the server with its own retries.
+pub(crate) struct Backoff {
+    state: Mutex<State>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                delay: INITIAL_DELAY,
+                until: None,
+            }),
+        }
+    }
+}
+
+impl Backoff {
+    /// Waits until the current backoff window (if any) has elapsed
+    pub(crate) async fn wait(&self) {
+        let until = self.state.lock().until;
+        if let Some(until) = until {
+            tokio::time::sleep_until(until).await;
+        }
+    }
+
+    /// Opens a new backoff window after a `429 Too Many Requests` response
+    ///
+    /// If a window is already open, the response was most likely caused by a
+    /// request sent before the window was opened, so it is not extended.
+    pub(crate) fn too_many_requests(&self) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        if matches!(state.until, Some(until) if until > now) {
+            return;
+        }
+
+        state.until = Some(now + state.delay);
+        state.delay = Duration::from_secs_f32(state.delay.as_secs_f32() * FACTOR).min(MAX_DELAY);
+    }
+
+    /// Resets the backoff delay after a request has been accepted
+    ///
+    /// A response to a request sent before the current window was opened does
+    /// not close it.
+    pub(crate) fn reset(&self) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        if matches!(state.until, Some(until) if until > now) {
+            return;
+        }
+
+        state.delay = INITIAL_DELAY;
+        state.until = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The timer has a millisecond resolution
+    fn assert_elapsed(started: Instant, expected: Duration) {
+        let elapsed = started.elapsed();
+        assert!(
+            elapsed >= expected && elapsed <= expected + Duration::from_millis(2),
+            "elapsed {elapsed:?}, expected {expected:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exponential_backoff() {
+        let backoff = Backoff::default();
+        let started = Instant::now();
+        backoff.wait().await;
+        assert_elapsed(started, Duration::ZERO);
+
+        backoff.too_many_requests();
+        backoff.wait().await;
+        assert_elapsed(started, INITIAL_DELAY);
+
+        backoff.too_many_requests();
+        backoff.wait().await;
+        assert_elapsed(started, INITIAL_DELAY * 3);
+
+        for _ in 0..10 {
+            backoff.too_many_requests();
+            backoff.wait().await;
+        }
+        assert_eq!(backoff.state.lock().delay, MAX_DELAY);
+
+        backoff.reset();
+        let started = Instant::now();
+        backoff.too_many_requests();
+        backoff.wait().await;
+        assert_elapsed(started, INITIAL_DELAY);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn open_window_is_not_extended_nor_closed() {
+        let backoff = Backoff::default();
+        let started = Instant::now();
+
+        backoff.too_many_requests();
+        // responses to the requests sent before the window was opened
+        backoff.too_many_requests();
+        backoff.reset();
+
+        backoff.wait().await;
+        assert_elapsed(started, INITIAL_DELAY);
+
+        backoff.too_many_requests();
+        backoff.wait().await;
+        assert_elapsed(started, INITIAL_DELAY * 3);
+    }
+}
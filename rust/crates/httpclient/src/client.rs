@@ -6,7 +6,9 @@ use reqwest::{
 };
 use serde::Deserialize;
 
-use crate::{HttpClientConfig, HttpClientError, HttpClientResult, Json, RequestBuilder};
+use crate::{
+    backoff::Backoff, HttpClientConfig, HttpClientError, HttpClientResult, Json, RequestBuilder,
+};
 
 /// LongPort HTTP client
 #[derive(Clone)]
@@ -14,6 +16,7 @@ pub struct HttpClient {
     pub(crate) http_cli: Client,
     pub(crate) config: Arc<HttpClientConfig>,
     pub(crate) default_headers: HeaderMap,
+    pub(crate) backoff: Arc<Backoff>,
 }
 
 impl HttpClient {
@@ -23,6 +26,7 @@ impl HttpClient {
             http_cli: Client::new(),
             config: Arc::new(config),
             default_headers: HeaderMap::new(),
+            backoff: Default::default(),
         }
     }
 
@@ -67,6 +67,12 @@ impl HttpClientConfig {
         Ok(config)
     }
 
+    /// Returns the app secret
+    #[inline]
+    pub fn app_secret(&self) -> &str {
+        &self.app_secret
+    }
+
     /// Specifies the url of the OpenAPI server.
     ///
     /// Default: <https://openapi.longportapp.com>
@@ -15,8 +15,6 @@ use crate::{
 const USER_AGENT: &str = "whaleapi-sdk";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const RETRY_COUNT: usize = 5;
-const RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
-const RETRY_FACTOR: f32 = 2.0;
 
 /// A JSON payload
 pub struct Json<T>(pub T);
@@ -210,6 +208,7 @@ where
             http_cli,
             config,
             default_headers,
+            ..
         } = &self.client;
         let timestamp = self
             .headers
@@ -302,30 +301,33 @@ where
     }
 
     /// Send request and get the response
+    ///
+    /// If the server responds with `429 Too Many Requests`, the request is
+    /// retried with an exponential backoff. The backoff is shared by all
+    /// requests sent through the same [`HttpClient`], so concurrent requests
+    /// wait together instead of retrying independently.
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn send(self) -> HttpClientResult<R> {
-        match self.do_send().await {
-            Ok(resp) => Ok(resp),
-            Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                let mut retry_delay = RETRY_INITIAL_DELAY;
-
-                for _ in 0..RETRY_COUNT {
-                    tokio::time::sleep(retry_delay).await;
-
-                    match self.do_send().await {
-                        Ok(resp) => return Ok(resp),
-                        Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                            retry_delay =
-                                Duration::from_secs_f32(retry_delay.as_secs_f32() * RETRY_FACTOR);
-                            continue;
-                        }
-                        Err(err) => return Err(err),
-                    }
-                }
+        let backoff = &self.client.backoff;
+        let mut retries = 0;
+
+        loop {
+            backoff.wait().await;
 
+            match self.do_send().await {
                 Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS))
+                    if retries < RETRY_COUNT =>
+                {
+                    backoff.too_many_requests();
+                    retries += 1;
+                }
+                res => {
+                    if res.is_ok() {
+                        backoff.reset();
+                    }
+                    return res;
+                }
             }
-            Err(err) => Err(err),
         }
     }
 }
@@ -0,0 +1,43 @@
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use longportwhale_wscli::codec::{Packet, PacketDecoder};
+
+const ORDER: &[u8] =
+    br#"{"symbol":"700.HK","side":"Buy","submitted_price":"300","executed_quantity":"100"},"#;
+
+fn push_packet(len: usize) -> Packet {
+    Packet::Push {
+        command_code: 18,
+        body: ORDER
+            .iter()
+            .copied()
+            .cycle()
+            .take(len)
+            .collect::<Vec<_>>()
+            .into(),
+        signature: None,
+    }
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for len in [256, 4096, 65536] {
+        let packet = push_packet(len);
+        let plain = Bytes::from(packet.encode().unwrap());
+        let gzip = Bytes::from(packet.encode_gzip(0).unwrap());
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("plain", len), &plain, |b, data| {
+            b.iter(|| Packet::decode(data.clone()).unwrap())
+        });
+
+        let mut decoder = PacketDecoder::new();
+        group.bench_with_input(BenchmarkId::new("gzip", len), &gzip, |b, data| {
+            b.iter(|| decoder.decode(data.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
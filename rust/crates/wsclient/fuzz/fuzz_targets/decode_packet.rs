@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use longportwhale_wscli::codec::Packet;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Packet::decode(data.to_vec());
+});
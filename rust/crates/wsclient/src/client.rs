@@ -5,6 +5,7 @@ use std::{
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use bytes::Bytes;
 use futures_util::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt, TryFutureExt,
@@ -18,13 +19,19 @@ use tokio::{
     sync::{mpsc, oneshot},
 };
 use tokio_tungstenite::{
-    tungstenite::{client::IntoClientRequest, http::Uri, Message},
+    tungstenite::{
+        client::IntoClientRequest,
+        http::Uri,
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message,
+    },
     MaybeTlsStream, WebSocketStream,
 };
 use url::Url;
 
 use crate::{
-    codec::Packet, WsClientError, WsClientResult, WsCloseReason, WsEvent, WsResponseErrorDetail,
+    codec::{HexPreview, Packet, PacketDecoder},
+    WsClientError, WsClientResult, WsCloseReason, WsEvent, WsResponseErrorDetail,
 };
 
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -32,6 +39,7 @@ const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(120);
 const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
 const RECONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
 
 const COMMAND_CODE_AUTH: u8 = 2;
 const COMMAND_CODE_RECONNECT: u8 = 3;
@@ -65,8 +73,57 @@ enum Command {
         command_code: u8,
         timeout_millis: u16,
         body: Vec<u8>,
-        reply_tx: oneshot::Sender<WsClientResult<Vec<u8>>>,
+        gzip_threshold: Option<usize>,
+        reply_tx: oneshot::Sender<WsClientResult<Bytes>>,
+    },
+    Close {
+        reply_tx: oneshot::Sender<WsClientResult<()>>,
     },
+    SetSignature {
+        signature: Option<PacketSignature>,
+    },
+}
+
+/// Signing of the requests and verification of the responses and pushes,
+/// see [`WsClient::set_signature`]
+#[derive(Debug, Clone)]
+pub struct PacketSignature {
+    key: Vec<u8>,
+    reject_unsigned_pushes: bool,
+}
+
+impl PacketSignature {
+    /// Create a new `PacketSignature` with the key of the signatures
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            reject_unsigned_pushes: false,
+        }
+    }
+
+    /// Rejects the pushes that are not signed, in addition to the ones that
+    /// are incorrectly signed
+    ///
+    /// Default: `false`
+    #[must_use]
+    pub fn reject_unsigned_pushes(self, reject_unsigned_pushes: bool) -> Self {
+        Self {
+            reject_unsigned_pushes,
+            ..self
+        }
+    }
+
+    fn check(&self, packet: &Packet) -> WsClientResult<()> {
+        match packet.verify(&self.key) {
+            Some(true) => Ok(()),
+            None if !(self.reject_unsigned_pushes && matches!(packet, Packet::Push { .. })) => {
+                Ok(())
+            }
+            _ => Err(WsClientError::InvalidSignature {
+                command_code: packet.command_code(),
+            }),
+        }
+    }
 }
 
 /// Rate limiter config
@@ -95,11 +152,13 @@ impl From<RateLimit> for RateLimiter {
 
 struct Context<'a> {
     request_id: u32,
-    inflight_requests: HashMap<u32, oneshot::Sender<WsClientResult<Vec<u8>>>>,
+    inflight_requests: HashMap<u32, oneshot::Sender<WsClientResult<Bytes>>>,
     sink: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
     stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
     command_rx: &'a mut mpsc::UnboundedReceiver<Command>,
     event_sender: &'a mut mpsc::UnboundedSender<WsEvent>,
+    signature: Option<PacketSignature>,
+    decoder: PacketDecoder,
 }
 
 impl<'a> Context<'a> {
@@ -116,6 +175,8 @@ impl<'a> Context<'a> {
             stream,
             command_rx,
             event_sender,
+            signature: None,
+            decoder: PacketDecoder::new(),
         }
     }
 
@@ -148,6 +209,10 @@ impl<'a> Context<'a> {
                 }
                 item = self.command_rx.recv() => {
                     match item {
+                        Some(Command::Close { reply_tx }) => {
+                            let _ = reply_tx.send(self.close().await);
+                            return Ok(());
+                        }
                         Some(command) => self.handle_command(command).await?,
                         None => return Ok(()),
                     }
@@ -167,24 +232,79 @@ impl<'a> Context<'a> {
                 command_code,
                 timeout_millis: timeout,
                 body,
+                gzip_threshold,
                 reply_tx,
             } => {
                 let request_id = self.get_request_id();
-                let msg = Message::Binary(
-                    Packet::Request {
-                        command_code,
-                        request_id,
-                        timeout_millis: timeout,
-                        body,
-                        signature: None,
+                let mut packet = Packet::Request {
+                    command_code,
+                    request_id,
+                    timeout_millis: timeout,
+                    body: body.into(),
+                    signature: None,
+                };
+                let res = match &self.signature {
+                    Some(signature) => packet.sign(&signature.key, rand::random()),
+                    None => Ok(()),
+                }
+                .and_then(|()| match gzip_threshold {
+                    Some(threshold) => packet.encode_gzip(threshold),
+                    None => packet.encode(),
+                });
+                let msg = match res {
+                    Ok(data) => Message::Binary(data),
+                    Err(err) => {
+                        let _ = reply_tx.send(Err(err.into()));
+                        return Ok(());
                     }
-                    .encode(),
-                );
+                };
                 self.inflight_requests.insert(request_id, reply_tx);
                 self.sink.send(msg).await?;
                 Ok(())
             }
+            Command::SetSignature { signature } => {
+                self.signature = signature;
+                Ok(())
+            }
+            Command::Close { .. } => unreachable!("handled by process_loop"),
+        }
+    }
+
+    /// Waits for the replies of the inflight requests, then sends a close
+    /// frame and waits for the server to acknowledge it
+    async fn close(&mut self) -> WsClientResult<()> {
+        let drain_inflight_requests = async {
+            while !self.inflight_requests.is_empty() {
+                match self.stream.next().await.transpose()? {
+                    Some(msg) => self.handle_message(msg).await?,
+                    None => return Err(WsClientError::ConnectionClosed { reason: None }),
+                }
+            }
+            Ok(())
+        };
+        // the requests still inflight after the timeout are cancelled
+        if let Ok(res) = tokio::time::timeout(CLOSE_TIMEOUT, drain_inflight_requests).await {
+            res?;
         }
+
+        self.sink
+            .send(Message::Close(Some(CloseFrame {
+                code: CloseCode::Normal,
+                reason: "".into(),
+            })))
+            .await?;
+
+        let wait_close_ack = async {
+            while let Some(msg) = self.stream.next().await.transpose()? {
+                if msg.is_close() {
+                    break;
+                }
+            }
+            Ok(())
+        };
+        tokio::time::timeout(CLOSE_TIMEOUT, wait_close_ack)
+            .await
+            .unwrap_or(Ok(()))
     }
 
     async fn handle_message(&mut self, msg: Message) -> WsClientResult<()> {
@@ -192,35 +312,50 @@ impl<'a> Context<'a> {
             Message::Ping(data) => {
                 self.sink.send(Message::Pong(data)).await?;
             }
-            Message::Binary(data) => match Packet::decode(&data)? {
-                Packet::Response {
-                    request_id,
-                    status,
-                    body,
-                    ..
-                } => {
-                    if let Some(sender) = self.inflight_requests.remove(&request_id) {
-                        if status == 0 {
-                            let _ = sender.send(Ok(body));
-                        } else {
-                            let detail = longport_proto::Error::decode(&*body).ok().map(
-                                |longport_proto::Error { code, msg }| WsResponseErrorDetail {
-                                    code,
-                                    msg,
-                                },
-                            );
-                            let _ =
-                                sender.send(Err(WsClientError::ResponseError { status, detail }));
+            Message::Binary(data) => {
+                let data = Bytes::from(data);
+                let packet = self.decoder.decode(data.clone()).map_err(|err| {
+                    tracing::trace!(error = %err, data = %HexPreview(&data), "malformed packet");
+                    err
+                })?;
+                if let Some(signature) = &self.signature {
+                    signature.check(&packet)?;
+                }
+                match packet {
+                    Packet::Response {
+                        request_id,
+                        status,
+                        body,
+                        ..
+                    } => {
+                        if let Some(sender) = self.inflight_requests.remove(&request_id) {
+                            if status == 0 {
+                                let _ = sender.send(Ok(body));
+                            } else {
+                                let detail = longport_proto::Error::decode(&*body).ok().map(
+                                    |longport_proto::Error { code, msg }| WsResponseErrorDetail {
+                                        code,
+                                        msg,
+                                    },
+                                );
+                                let _ = sender
+                                    .send(Err(WsClientError::ResponseError { status, detail }));
+                            }
                         }
                     }
+                    Packet::Push {
+                        command_code, body, ..
+                    } => {
+                        let _ = self.event_sender.send(WsEvent::Push { command_code, body });
+                    }
+                    Packet::Request { command_code, .. } => {
+                        tracing::trace!(command_code, "unexpected request packet");
+                        return Err(WsClientError::UnexpectedMessage {
+                            kind: "request packet",
+                        });
+                    }
                 }
-                Packet::Push {
-                    command_code, body, ..
-                } => {
-                    let _ = self.event_sender.send(WsEvent::Push { command_code, body });
-                }
-                _ => return Err(WsClientError::UnexpectedResponse),
-            },
+            }
             Message::Close(Some(close_frame)) => {
                 return Err(WsClientError::ConnectionClosed {
                     reason: Some(WsCloseReason {
@@ -229,7 +364,16 @@ impl<'a> Context<'a> {
                     }),
                 });
             }
-            _ => return Err(WsClientError::UnexpectedResponse),
+            msg => {
+                let kind = match msg {
+                    Message::Text(_) => "text",
+                    Message::Pong(_) => "pong",
+                    Message::Close(_) => "close",
+                    _ => "frame",
+                };
+                tracing::trace!(kind, data = %HexPreview(&msg.into_data()), "unexpected message");
+                return Err(WsClientError::UnexpectedMessage { kind });
+            }
         }
 
         Ok(())
@@ -258,6 +402,7 @@ impl WsSession {
 pub struct WsClient {
     command_tx: mpsc::UnboundedSender<Command>,
     rate_limit: Arc<HashMap<u8, RateLimiter>>,
+    gzip_threshold: Option<usize>,
 }
 
 impl WsClient {
@@ -281,6 +426,7 @@ impl WsClient {
                     .map(|(cmd, rate_limit)| (cmd, rate_limit.into()))
                     .collect(),
             ),
+            gzip_threshold: None,
         })
     }
 
@@ -294,6 +440,26 @@ impl WsClient {
         );
     }
 
+    /// Set the size in bytes from which the request bodies are compressed
+    /// with gzip, `None` disables the compression
+    ///
+    /// Default: `None`
+    pub fn set_gzip_threshold(&mut self, threshold: Option<usize>) {
+        self.gzip_threshold = threshold;
+    }
+
+    /// Set the key used to sign the requests and to verify the signed
+    /// responses and pushes, `None` disables the signatures
+    ///
+    /// Applies to the requests sent after this. A response or push with an
+    /// invalid signature closes the connection with
+    /// [`WsClientError::InvalidSignature`].
+    pub fn set_signature(&self, signature: Option<PacketSignature>) -> WsClientResult<()> {
+        self.command_tx
+            .send(Command::SetSignature { signature })
+            .map_err(|_| WsClientError::ClientClosed)
+    }
+
     /// Send an authentication request to get a [`WsSession`]
     ///
     /// Reference: <https://open.longportapp.com/en/docs/socket-token-api>
@@ -345,13 +511,26 @@ impl WsClient {
         })
     }
 
+    /// Gracefully close the connection
+    ///
+    /// Waits for the replies of the inflight requests, sends a websocket close
+    /// frame and waits for the server to acknowledge it. The requests sent
+    /// after this fail with [`WsClientError::ClientClosed`].
+    pub async fn close(&self) -> WsClientResult<()> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.command_tx
+            .send(Command::Close { reply_tx })
+            .map_err(|_| WsClientError::ClientClosed)?;
+        reply_rx.await.map_err(|_| WsClientError::ClientClosed)?
+    }
+
     /// Send a raw request
     pub async fn request_raw(
         &self,
         command_code: u8,
         timeout: Option<Duration>,
         body: Vec<u8>,
-    ) -> WsClientResult<Vec<u8>> {
+    ) -> WsClientResult<Bytes> {
         if let Some(rate_limit) = self.rate_limit.get(&command_code) {
             rate_limit.acquire_one().await;
         }
@@ -362,6 +541,7 @@ impl WsClient {
                 command_code,
                 timeout_millis: timeout.unwrap_or(REQUEST_TIMEOUT).as_millis().min(60000) as u16,
                 body,
+                gzip_threshold: self.gzip_threshold,
                 reply_tx,
             })
             .map_err(|_| WsClientError::ClientClosed)?;
@@ -388,7 +568,7 @@ impl WsClient {
         let resp = self
             .request_raw(command_code, timeout, req.encode_to_vec())
             .await?;
-        Ok(R::decode(&*resp)?)
+        Ok(R::decode(resp)?)
     }
 }
 
@@ -429,13 +609,9 @@ async fn client_loop(
 ) {
     let mut ctx = Context::new(conn, &mut command_tx, &mut event_sender);
 
-    let res = ctx.process_loop().await;
-    match res {
-        Ok(()) => return,
-        Err(err) => {
-            ctx.send_event(WsEvent::Error(err));
-        }
-    };
+    if let Err(err) = ctx.process_loop().await {
+        ctx.send_event(WsEvent::Error(err));
+    }
 
     for sender in ctx.inflight_requests.into_values() {
         let _ = sender.send(Err(WsClientError::Cancelled));
@@ -1,30 +1,65 @@
-use std::io::{Cursor, Read};
+//! Binary packet codec of the LongPort websocket protocol
+//!
+//! Reference: <https://open.longportapp.com/en/docs/socket/protocol/overview>
 
-use byteorder::{ReadBytesExt, WriteBytesExt, BE};
-use flate2::read::GzDecoder;
+#![cfg_attr(not(feature = "codec"), allow(unreachable_pub))]
 
-use crate::{WsClientError, WsClientResult};
+use std::io::{Read, Write};
 
-const PACKAGE_TYPE_REQUEST: u8 = 1;
-const PACKAGE_TYPE_RESPONSE: u8 = 2;
-const PACKAGE_TYPE_PUSH: u8 = 3;
+use byteorder::{WriteBytesExt, BE};
+use bytes::{Bytes, BytesMut};
+use flate2::{bufread::GzDecoder, write::GzEncoder, Compression};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 
-#[derive(Debug)]
-pub(crate) struct Signature {
-    pub(crate) nonce: [u8; 8],
-    pub(crate) signature: [u8; 16],
+use crate::CodecError;
+
+/// Packet type of [`Packet::Request`]
+pub const PACKAGE_TYPE_REQUEST: u8 = 1;
+/// Packet type of [`Packet::Response`]
+pub const PACKAGE_TYPE_RESPONSE: u8 = 2;
+/// Packet type of [`Packet::Push`]
+pub const PACKAGE_TYPE_PUSH: u8 = 3;
+
+/// Maximum length of an encoded body, its length is written in 3 bytes
+const MAX_ENCODED_BODY_LEN: usize = 0xff_ffff;
+
+/// Signature of a packet
+///
+/// The signature is the first 16 bytes of the HMAC-SHA256 of the nonce
+/// followed by the packet encoded without signature and without compression.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    /// Nonce
+    pub nonce: [u8; 8],
+    /// Signature
+    pub signature: [u8; 16],
 }
 
-#[derive(Debug)]
-pub(crate) struct PacketHeader {
-    ty: u8,
-    verify: bool,
-    gzip: bool,
+impl Signature {
+    fn mac(key: &[u8], nonce: &[u8; 8], data: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+        mac.update(nonce);
+        mac.update(data);
+        mac
+    }
+}
+
+/// The first byte of a packet
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PacketHeader {
+    /// Packet type, e.g. [`PACKAGE_TYPE_REQUEST`]
+    pub ty: u8,
+    /// `true` if the packet is followed by a [`Signature`]
+    pub verify: bool,
+    /// `true` if the body is compressed with gzip
+    pub gzip: bool,
 }
 
 impl PacketHeader {
+    /// Encodes the header
     #[inline]
-    pub(crate) fn encode(&self) -> u8 {
+    pub fn encode(&self) -> u8 {
         let mut data = self.ty & 0b00001111;
         if self.verify {
             data |= 0b00010000;
@@ -35,8 +70,9 @@ impl PacketHeader {
         data
     }
 
+    /// Decodes the header
     #[inline]
-    pub(crate) fn decode(data: u8) -> PacketHeader {
+    pub fn decode(data: u8) -> PacketHeader {
         let ty = data & 0b00001111;
         let verify = (data & 0b00010000) > 0;
         let gzip = (data & 0b00100000) > 0;
@@ -44,97 +80,198 @@ impl PacketHeader {
     }
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
-pub(crate) enum Packet {
+/// A packet of the LongPort websocket protocol
+#[derive(Debug, Clone)]
+pub enum Packet {
+    /// Request
     Request {
+        /// Command code
         command_code: u8,
+        /// Request id
         request_id: u32,
+        /// Timeout in milliseconds
         timeout_millis: u16,
-        body: Vec<u8>,
+        /// Body, decompressed
+        body: Bytes,
+        /// Signature
         signature: Option<Signature>,
     },
+    /// Response
     Response {
+        /// Command code
         command_code: u8,
+        /// Request id
         request_id: u32,
+        /// Status code, `0` means success
         status: u8,
-        body: Vec<u8>,
+        /// Body, decompressed
+        body: Bytes,
+        /// Signature
         signature: Option<Signature>,
     },
+    /// Push
     Push {
+        /// Command code
         command_code: u8,
-        body: Vec<u8>,
+        /// Body, decompressed
+        body: Bytes,
+        /// Signature
         signature: Option<Signature>,
     },
 }
 
 impl Packet {
-    pub(crate) fn encode(&self) -> Vec<u8> {
+    /// Encodes the packet without compressing the body
+    ///
+    /// Returns [`CodecError::BodyTooLarge`] if the body is longer than
+    /// `0xFFFFFF` bytes.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_body(None, self.signature())
+    }
+
+    /// Encodes the packet, the body is compressed with gzip if it is at least
+    /// `threshold` bytes
+    ///
+    /// Returns [`CodecError::BodyTooLarge`] if the body, once compressed, is
+    /// longer than `0xFFFFFF` bytes.
+    pub fn encode_gzip(&self, threshold: usize) -> Result<Vec<u8>, CodecError> {
+        self.encode_body(Some(threshold), self.signature())
+    }
+
+    /// Returns the signature of the packet
+    #[inline]
+    pub fn signature(&self) -> Option<&Signature> {
+        match self {
+            Packet::Request { signature, .. }
+            | Packet::Response { signature, .. }
+            | Packet::Push { signature, .. } => signature.as_ref(),
+        }
+    }
+
+    /// Signs the packet with `key`, replacing the current signature if any
+    ///
+    /// Returns [`CodecError::BodyTooLarge`] if the body is longer than
+    /// `0xFFFFFF` bytes, since the signature covers the packet encoded without
+    /// compression.
+    pub fn sign(&mut self, key: &[u8], nonce: [u8; 8]) -> Result<(), CodecError> {
+        let mac = Signature::mac(key, &nonce, &self.encode_body(None, None)?);
+        let mut signature = [0; 16];
+        signature.copy_from_slice(&mac.finalize().into_bytes()[..16]);
+        let new_signature = Some(Signature { nonce, signature });
+        match self {
+            Packet::Request { signature, .. }
+            | Packet::Response { signature, .. }
+            | Packet::Push { signature, .. } => *signature = new_signature,
+        }
+        Ok(())
+    }
+
+    /// Verifies the signature of the packet with `key`
+    ///
+    /// Returns `None` if the packet is not signed, and `Some(false)` if its
+    /// body is too large to be signed.
+    pub fn verify(&self, key: &[u8]) -> Option<bool> {
+        let signature = self.signature()?;
+        let Ok(data) = self.encode_body(None, None) else {
+            return Some(false);
+        };
+        let mac = Signature::mac(key, &signature.nonce, &data);
+        Some(mac.verify_truncated_left(&signature.signature).is_ok())
+    }
+
+    fn encode_body(
+        &self,
+        gzip_threshold: Option<usize>,
+        signature: Option<&Signature>,
+    ) -> Result<Vec<u8>, CodecError> {
+        let (ty, command_code, body) = match self {
+            Packet::Request {
+                command_code, body, ..
+            } => (PACKAGE_TYPE_REQUEST, command_code, body),
+            Packet::Response {
+                command_code, body, ..
+            } => (PACKAGE_TYPE_RESPONSE, command_code, body),
+            Packet::Push {
+                command_code, body, ..
+            } => (PACKAGE_TYPE_PUSH, command_code, body),
+        };
+
+        let compressed = match gzip_threshold {
+            Some(threshold) if body.len() >= threshold => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                let _ = encoder.write_all(body);
+                encoder.finish().ok()
+            }
+            _ => None,
+        };
+
+        let header = PacketHeader {
+            ty,
+            verify: signature.is_some(),
+            gzip: compressed.is_some(),
+        }
+        .encode();
+        let mut data = vec![header, *command_code];
+
         match self {
             Packet::Request {
-                command_code,
                 request_id,
-                timeout_millis: timeout,
-                body,
-                signature,
+                timeout_millis,
+                ..
             } => {
-                let header = PacketHeader {
-                    ty: PACKAGE_TYPE_REQUEST,
-                    verify: signature.is_some(),
-                    gzip: false,
-                }
-                .encode();
-                let mut data = vec![header, *command_code];
-
                 let _ = data.write_u32::<BE>(*request_id);
-                let _ = data.write_u16::<BE>(*timeout);
-                let _ = data.write_u24::<BE>(body.len() as u32);
-
-                data.extend(body);
+                let _ = data.write_u16::<BE>(*timeout_millis);
+            }
+            Packet::Response {
+                request_id, status, ..
+            } => {
+                let _ = data.write_u32::<BE>(*request_id);
+                let _ = data.write_u8(*status);
+            }
+            Packet::Push { .. } => {}
+        }
 
-                if let Some(signature) = signature {
-                    data.extend_from_slice(&signature.nonce);
-                    data.extend_from_slice(&signature.signature);
-                }
+        let body = compressed.as_deref().unwrap_or(&body[..]);
+        if body.len() > MAX_ENCODED_BODY_LEN {
+            return Err(CodecError::BodyTooLarge {
+                command_code: *command_code,
+                len: body.len(),
+                limit: MAX_ENCODED_BODY_LEN,
+            });
+        }
+        let _ = data.write_u24::<BE>(body.len() as u32);
+        data.extend_from_slice(body);
 
-                data
-            }
-            Packet::Response { .. } => unimplemented!(),
-            Packet::Push { .. } => unimplemented!(),
+        if let Some(signature) = signature {
+            data.extend_from_slice(&signature.nonce);
+            data.extend_from_slice(&signature.signature);
         }
+
+        Ok(data)
     }
 
-    pub(crate) fn decode(data: &[u8]) -> WsClientResult<Packet> {
-        if data.is_empty() {
-            return Err(WsClientError::UnexpectedResponse);
-        }
+    /// Decodes a packet, the body is decompressed if needed
+    ///
+    /// The body of an uncompressed packet is a slice of `data`, use a
+    /// [`PacketDecoder`] to reuse the decompression buffer between packets.
+    #[cfg_attr(not(any(feature = "codec", feature = "mock")), allow(dead_code))]
+    pub fn decode(data: impl Into<Bytes>) -> Result<Packet, CodecError> {
+        PacketDecoder::new().decode(data)
+    }
 
-        let header = PacketHeader::decode(data[0]);
-        let res = match header.ty {
-            PACKAGE_TYPE_REQUEST => parse_request(&header, &data[1..]),
-            PACKAGE_TYPE_RESPONSE => parse_response(&header, &data[1..]),
-            PACKAGE_TYPE_PUSH => parse_push(&header, &data[1..]),
-            _ => return Err(WsClientError::UnexpectedResponse),
-        };
-        res.and_then(|packet| {
-            if header.gzip {
-                packet.map_body(|body| {
-                    let mut new_body = Vec::new();
-                    let mut decoder = GzDecoder::new(&*body);
-                    decoder.read_to_end(&mut new_body)?;
-                    Ok(new_body)
-                })
-            } else {
-                Ok(packet)
-            }
-        })
-        .map_err(|_| WsClientError::UnexpectedResponse)
+    /// Returns the command code of the packet
+    pub fn command_code(&self) -> u8 {
+        match self {
+            Packet::Request { command_code, .. }
+            | Packet::Response { command_code, .. }
+            | Packet::Push { command_code, .. } => *command_code,
+        }
     }
 
     #[inline]
     fn map_body<F, Err>(self, f: F) -> Result<Self, Err>
     where
-        F: FnOnce(Vec<u8>) -> Result<Vec<u8>, Err>,
+        F: FnOnce(Bytes) -> Result<Bytes, Err>,
     {
         Ok(match self {
             Packet::Request {
@@ -176,81 +313,582 @@ impl Packet {
     }
 }
 
-fn parse_signature(rdr: &mut impl Read) -> std::io::Result<Signature> {
-    let mut nonce = [0; 8];
-    let mut signature = [0; 16];
-    rdr.read_exact(&mut nonce)?;
-    rdr.read_exact(&mut signature)?;
-    Ok(Signature { nonce, signature })
+/// Decodes packets, reusing the decompression buffer between them
+///
+/// The decompressed bodies are split off the buffer, whose allocation is
+/// reused once the bodies of the previous packets have been dropped.
+#[derive(Debug)]
+pub struct PacketDecoder {
+    buf: BytesMut,
+    max_body_len: usize,
+}
+
+impl Default for PacketDecoder {
+    fn default() -> Self {
+        Self {
+            buf: BytesMut::new(),
+            max_body_len: Self::DEFAULT_MAX_BODY_LEN,
+        }
+    }
+}
+
+impl PacketDecoder {
+    /// Size by which the decompression buffer grows
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    /// Default maximum size of a decompressed body, the largest body length
+    /// the 24-bit length field of a packet can hold
+    pub const DEFAULT_MAX_BODY_LEN: usize = 0xff_ffff;
+
+    /// Create a new `PacketDecoder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size of a decompressed body
+    ///
+    /// Decompressing stops as soon as the body exceeds this size, which is
+    /// reported as [`CodecError::DecompressedTooLarge`].
+    ///
+    /// Default: [`PacketDecoder::DEFAULT_MAX_BODY_LEN`]
+    #[must_use]
+    #[cfg_attr(not(feature = "codec"), allow(dead_code))]
+    pub fn max_body_len(self, max_body_len: usize) -> Self {
+        Self {
+            max_body_len,
+            ..self
+        }
+    }
+
+    /// Decodes a packet, see [`Packet::decode`]
+    ///
+    /// Never panics, a malformed packet is reported as a [`CodecError`]
+    /// locating the offending bytes.
+    pub fn decode(&mut self, data: impl Into<Bytes>) -> Result<Packet, CodecError> {
+        let data = data.into();
+        let Some(&first) = data.first() else {
+            return Err(CodecError::Empty);
+        };
+
+        let header = PacketHeader::decode(first);
+        let mut rdr = Reader::new(&data);
+        let packet = match header.ty {
+            PACKAGE_TYPE_REQUEST => {
+                let command_code = rdr.header_u8(header.ty, None)?;
+                let request_id = rdr.header_u32(header.ty, command_code)?;
+                let timeout_millis = rdr.header_u16(header.ty, command_code)?;
+                let body = rdr.body(header.ty, command_code)?;
+                Packet::Request {
+                    command_code,
+                    request_id,
+                    timeout_millis,
+                    body,
+                    signature: rdr.signature(&header, command_code)?,
+                }
+            }
+            PACKAGE_TYPE_RESPONSE => {
+                let command_code = rdr.header_u8(header.ty, None)?;
+                let request_id = rdr.header_u32(header.ty, command_code)?;
+                let status = rdr.header_u8(header.ty, Some(command_code))?;
+                let body = rdr.body(header.ty, command_code)?;
+                Packet::Response {
+                    command_code,
+                    request_id,
+                    status,
+                    body,
+                    signature: rdr.signature(&header, command_code)?,
+                }
+            }
+            PACKAGE_TYPE_PUSH => {
+                let command_code = rdr.header_u8(header.ty, None)?;
+                let body = rdr.body(header.ty, command_code)?;
+                Packet::Push {
+                    command_code,
+                    body,
+                    signature: rdr.signature(&header, command_code)?,
+                }
+            }
+            ty => return Err(CodecError::UnknownPacketType { ty }),
+        };
+
+        if rdr.offset != data.len() {
+            return Err(CodecError::TrailingBytes {
+                command_code: packet.command_code(),
+                offset: rdr.offset,
+                len: data.len() - rdr.offset,
+            });
+        }
+
+        if header.gzip {
+            let command_code = packet.command_code();
+            packet.map_body(|body| self.gunzip(&body, command_code))
+        } else {
+            Ok(packet)
+        }
+    }
+
+    fn gunzip(&mut self, data: &[u8], command_code: u8) -> Result<Bytes, CodecError> {
+        let mut decoder = GzDecoder::new(data);
+        let mut len = 0;
+        loop {
+            if len == self.buf.len() {
+                // reclaims the allocation if the previous bodies are dropped
+                self.buf.reserve(Self::CHUNK_SIZE);
+                self.buf.resize(self.buf.capacity(), 0);
+            }
+            // reads at most one byte past the limit to detect an oversized body
+            let end = self.buf.len().min(self.max_body_len.saturating_add(1));
+            match decoder.read(&mut self.buf[len..end]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(source) => {
+                    self.buf.clear();
+                    return Err(CodecError::Gzip {
+                        command_code,
+                        source,
+                    });
+                }
+            }
+            if len > self.max_body_len {
+                self.buf.clear();
+                return Err(CodecError::DecompressedTooLarge {
+                    command_code,
+                    limit: self.max_body_len,
+                });
+            }
+        }
+        self.buf.truncate(len);
+        Ok(self.buf.split().freeze())
+    }
+}
+
+/// Reads the fields of a packet, tracking the offset for the errors
+struct Reader<'a> {
+    data: &'a Bytes,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a Bytes) -> Self {
+        // skip the header byte
+        Self { data, offset: 1 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn header_field<const N: usize>(
+        &mut self,
+        ty: u8,
+        command_code: Option<u8>,
+    ) -> Result<[u8; N], CodecError> {
+        match self.take(N) {
+            Some(bytes) => Ok(bytes.try_into().expect("N bytes")),
+            None => Err(CodecError::TruncatedHeader {
+                ty,
+                command_code,
+                len: self.data.len(),
+            }),
+        }
+    }
+
+    fn header_u8(&mut self, ty: u8, command_code: Option<u8>) -> Result<u8, CodecError> {
+        let [value] = self.header_field(ty, command_code)?;
+        Ok(value)
+    }
+
+    fn header_u16(&mut self, ty: u8, command_code: u8) -> Result<u16, CodecError> {
+        Ok(u16::from_be_bytes(
+            self.header_field(ty, Some(command_code))?,
+        ))
+    }
+
+    fn header_u32(&mut self, ty: u8, command_code: u8) -> Result<u32, CodecError> {
+        Ok(u32::from_be_bytes(
+            self.header_field(ty, Some(command_code))?,
+        ))
+    }
+
+    fn body(&mut self, ty: u8, command_code: u8) -> Result<Bytes, CodecError> {
+        let [a, b, c] = self.header_field(ty, Some(command_code))?;
+        let expected = u32::from_be_bytes([0, a, b, c]) as usize;
+        let offset = self.offset;
+        match self.take(expected) {
+            Some(_) => Ok(self.data.slice(offset..self.offset)),
+            None => Err(CodecError::TruncatedBody {
+                command_code,
+                offset,
+                expected,
+                available: self.data.len() - offset,
+            }),
+        }
+    }
+
+    fn signature(
+        &mut self,
+        header: &PacketHeader,
+        command_code: u8,
+    ) -> Result<Option<Signature>, CodecError> {
+        if !header.verify {
+            return Ok(None);
+        }
+        let offset = self.offset;
+        match (self.take(8), self.take(16)) {
+            (Some(nonce), Some(signature)) => Ok(Some(Signature {
+                nonce: nonce.try_into().expect("8 bytes"),
+                signature: signature.try_into().expect("16 bytes"),
+            })),
+            _ => Err(CodecError::TruncatedSignature {
+                command_code,
+                offset,
+            }),
+        }
+    }
 }
 
-fn parse_request(header: &PacketHeader, data: &[u8]) -> std::io::Result<Packet> {
-    let mut cursor = Cursor::new(data);
-    let command_code = cursor.read_u8()?;
-    let request_id = cursor.read_u32::<BE>()?;
-    let timeout = cursor.read_u16::<BE>()?;
-    let body_len = cursor.read_u24::<BE>()?;
-
-    let mut body = vec![0; body_len as usize];
-    cursor.read_exact(&mut body)?;
-
-    let signature = if header.verify {
-        Some(parse_signature(&mut cursor)?)
-    } else {
-        None
-    };
-
-    Ok(Packet::Request {
-        command_code,
-        request_id,
-        timeout_millis: timeout,
-        body,
-        signature,
-    })
+/// Formats the first bytes of a packet as a hex dump for the logs
+pub(crate) struct HexPreview<'a>(pub(crate) &'a [u8]);
+
+impl HexPreview<'_> {
+    const MAX_LEN: usize = 128;
 }
 
-fn parse_response(header: &PacketHeader, data: &[u8]) -> std::io::Result<Packet> {
-    let mut cursor = Cursor::new(data);
-    let command_code = cursor.read_u8()?;
-    let request_id = cursor.read_u32::<BE>()?;
-    let status = cursor.read_u8()?;
-    let body_len = cursor.read_u24::<BE>()?;
-    let mut body = vec![0; body_len as usize];
-    cursor.read_exact(&mut body)?;
-
-    let signature = if header.verify {
-        Some(parse_signature(&mut cursor)?)
-    } else {
-        None
-    };
-
-    debug_assert_eq!(cursor.position() as usize, data.len());
-
-    Ok(Packet::Response {
-        command_code,
-        request_id,
-        status,
-        body,
-        signature,
-    })
+impl std::fmt::Display for HexPreview<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (n, line) in self.0.chunks(16).take(Self::MAX_LEN / 16).enumerate() {
+            write!(f, "\n{:08x} ", n * 16)?;
+            for byte in line {
+                write!(f, " {byte:02x}")?;
+            }
+        }
+        if self.0.len() > Self::MAX_LEN {
+            write!(f, "\n... ({} bytes)", self.0.len())?;
+        }
+        Ok(())
+    }
 }
 
-fn parse_push(header: &PacketHeader, data: &[u8]) -> std::io::Result<Packet> {
-    let mut cursor = Cursor::new(data);
-    let command_code = cursor.read_u8()?;
-    let body_len = cursor.read_u24::<BE>()?;
-    let mut body = vec![0; body_len as usize];
-    cursor.read_exact(&mut body)?;
-
-    let signature = if header.verify {
-        Some(parse_signature(&mut cursor)?)
-    } else {
-        None
-    };
-
-    Ok(Packet::Push {
-        command_code,
-        body,
-        signature,
-    })
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn encode_and_decode() {
+        let packets = [
+            Packet::Request {
+                command_code: 16,
+                request_id: 1,
+                timeout_millis: 5000,
+                body: Bytes::from(vec![1; 100]),
+                signature: None,
+            },
+            Packet::Response {
+                command_code: 16,
+                request_id: 1,
+                status: 0,
+                body: Bytes::from(vec![2; 100]),
+                signature: Some(Signature {
+                    nonce: [3; 8],
+                    signature: [4; 16],
+                }),
+            },
+            Packet::Push {
+                command_code: 18,
+                body: Bytes::from(vec![5; 100]),
+                signature: None,
+            },
+        ];
+
+        for packet in packets {
+            for (data, gzip) in [
+                (packet.encode().unwrap(), false),
+                (packet.encode_gzip(1000).unwrap(), false),
+                (packet.encode_gzip(100).unwrap(), true),
+            ] {
+                assert_eq!(PacketHeader::decode(data[0]).gzip, gzip);
+                let decoded = Packet::decode(data).unwrap();
+                assert_eq!(format!("{decoded:?}"), format!("{packet:?}"));
+            }
+        }
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let mut packet = Packet::Push {
+            command_code: 18,
+            body: Bytes::from(vec![1; 100]),
+            signature: None,
+        };
+        assert_eq!(packet.verify(b"key"), None);
+
+        packet.sign(b"key", [1; 8]).unwrap();
+        assert_eq!(packet.verify(b"key"), Some(true));
+        assert_eq!(packet.verify(b"other key"), Some(false));
+
+        // the signature does not depend on the compression
+        let decoded = Packet::decode(packet.encode_gzip(10).unwrap()).unwrap();
+        assert_eq!(decoded.verify(b"key"), Some(true));
+
+        let mut data = packet.encode().unwrap();
+        data[10] ^= 1;
+        assert_eq!(Packet::decode(data).unwrap().verify(b"key"), Some(false));
+    }
+
+    #[test]
+    fn malformed_packets() {
+        let push = Packet::Push {
+            command_code: 18,
+            body: Bytes::from(vec![1; 10]),
+            signature: None,
+        }
+        .encode()
+        .unwrap();
+
+        assert!(matches!(
+            Packet::decode(Bytes::new()),
+            Err(CodecError::Empty)
+        ));
+        assert!(matches!(
+            Packet::decode(vec![0]),
+            Err(CodecError::UnknownPacketType { ty: 0 })
+        ));
+        assert!(matches!(
+            Packet::decode(push[..3].to_vec()),
+            Err(CodecError::TruncatedHeader {
+                ty: PACKAGE_TYPE_PUSH,
+                command_code: Some(18),
+                len: 3
+            })
+        ));
+        assert!(matches!(
+            Packet::decode(push[..8].to_vec()),
+            Err(CodecError::TruncatedBody {
+                command_code: 18,
+                offset: 5,
+                expected: 10,
+                available: 3
+            })
+        ));
+
+        let mut data = push.clone();
+        data.push(0);
+        assert!(matches!(
+            Packet::decode(data),
+            Err(CodecError::TrailingBytes {
+                command_code: 18,
+                offset: 15,
+                len: 1
+            })
+        ));
+
+        let mut data = push.clone();
+        data[0] |= 0x10;
+        assert!(matches!(
+            Packet::decode(data),
+            Err(CodecError::TruncatedSignature {
+                command_code: 18,
+                offset: 15
+            })
+        ));
+
+        let mut data = push;
+        data[0] |= 0x20;
+        assert!(matches!(
+            Packet::decode(data),
+            Err(CodecError::Gzip {
+                command_code: 18,
+                ..
+            })
+        ));
+    }
+
+    fn random_packet(rng: &mut StdRng) -> Packet {
+        let body_len = rng.gen_range(0..300);
+        let body = Bytes::from(
+            (0..body_len)
+                .map(|_| rng.gen_range(0..4))
+                .collect::<Vec<u8>>(),
+        );
+        let signature = rng.gen_bool(0.5).then(|| Signature {
+            nonce: rng.gen(),
+            signature: rng.gen(),
+        });
+        match rng.gen_range(0..3) {
+            0 => Packet::Request {
+                command_code: rng.gen(),
+                request_id: rng.gen(),
+                timeout_millis: rng.gen(),
+                body,
+                signature,
+            },
+            1 => Packet::Response {
+                command_code: rng.gen(),
+                request_id: rng.gen(),
+                status: rng.gen(),
+                body,
+                signature,
+            },
+            _ => Packet::Push {
+                command_code: rng.gen(),
+                body,
+                signature,
+            },
+        }
+    }
+
+    #[test]
+    fn random_packets_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let packet = random_packet(&mut rng);
+            let data = packet.encode_gzip(rng.gen_range(0..400)).unwrap();
+            let decoded = Packet::decode(data).unwrap();
+            assert_eq!(format!("{decoded:?}"), format!("{packet:?}"));
+        }
+    }
+
+    #[test]
+    fn malformed_packets_never_panic() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..10000 {
+            let len = rng.gen_range(0..64);
+            let data = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let _ = Packet::decode(data);
+        }
+
+        for _ in 0..1000 {
+            let data = random_packet(&mut rng)
+                .encode_gzip(rng.gen_range(0..400))
+                .unwrap();
+
+            // every truncation is an error
+            let len = rng.gen_range(0..data.len());
+            assert!(Packet::decode(data[..len].to_vec()).is_err());
+
+            let mut mutated = data.clone();
+            for _ in 0..rng.gen_range(1..4) {
+                let offset = rng.gen_range(0..mutated.len());
+                mutated[offset] = rng.gen();
+            }
+            let _ = Packet::decode(mutated);
+
+            let mut extended = data;
+            extended.extend((0..rng.gen_range(1..32)).map(|_| rng.gen::<u8>()));
+            let _ = Packet::decode(extended);
+        }
+    }
+
+    #[test]
+    fn decompressed_too_large() {
+        // a few kilobytes expanding to 32 MiB
+        let data = Packet::Push {
+            command_code: 18,
+            body: Bytes::from(vec![0; 32 * 1024 * 1024]),
+            signature: None,
+        }
+        .encode_gzip(0)
+        .unwrap();
+        assert!(data.len() < 64 * 1024);
+
+        assert!(matches!(
+            Packet::decode(data.clone()),
+            Err(CodecError::DecompressedTooLarge {
+                command_code: 18,
+                limit: PacketDecoder::DEFAULT_MAX_BODY_LEN
+            })
+        ));
+
+        let mut decoder = PacketDecoder::new().max_body_len(1000);
+        assert!(matches!(
+            decoder.decode(data),
+            Err(CodecError::DecompressedTooLarge {
+                command_code: 18,
+                limit: 1000
+            })
+        ));
+
+        let data = Packet::Push {
+            command_code: 18,
+            body: Bytes::from(vec![0; 1000]),
+            signature: None,
+        }
+        .encode_gzip(0)
+        .unwrap();
+        let Packet::Push { body, .. } = decoder.decode(data).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(body.len(), 1000);
+    }
+
+    #[test]
+    fn body_too_large() {
+        let mut packet = Packet::Push {
+            command_code: 18,
+            body: Bytes::from(vec![0; MAX_ENCODED_BODY_LEN + 1]),
+            signature: None,
+        };
+        assert!(matches!(
+            packet.encode(),
+            Err(CodecError::BodyTooLarge {
+                command_code: 18,
+                len,
+                limit: MAX_ENCODED_BODY_LEN,
+            }) if len == MAX_ENCODED_BODY_LEN + 1
+        ));
+        assert!(matches!(
+            packet.sign(b"key", [1; 8]),
+            Err(CodecError::BodyTooLarge { .. })
+        ));
+
+        // the limit applies to the compressed body
+        assert!(packet.encode_gzip(0).is_ok());
+    }
+
+    #[test]
+    fn reuse_decompression_buffer() {
+        let data = Packet::Push {
+            command_code: 18,
+            body: Bytes::from(vec![1; 1000]),
+            signature: None,
+        }
+        .encode_gzip(0)
+        .unwrap();
+
+        let mut decoder = PacketDecoder::new();
+        let Packet::Push { body, .. } = decoder.decode(data.clone()).unwrap() else {
+            unreachable!()
+        };
+        let ptr = body.as_ptr();
+        drop(body);
+
+        let Packet::Push { body, .. } = decoder.decode(data).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(body.as_ptr(), ptr);
+        assert_eq!(body, vec![1; 1000]);
+    }
+
+    #[test]
+    fn uncompressed_body_is_a_slice() {
+        let data = Bytes::from(
+            Packet::Push {
+                command_code: 18,
+                body: Bytes::from(vec![1; 100]),
+                signature: None,
+            }
+            .encode()
+            .unwrap(),
+        );
+        let Packet::Push { body, .. } = Packet::decode(data.clone()).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(body.as_ptr(), data[5..].as_ptr());
+    }
 }
@@ -2,7 +2,7 @@ use prost::DecodeError;
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 
 /// Connection close reason
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WsCloseReason {
     /// Close code
     pub code: CloseCode,
@@ -27,6 +27,17 @@ pub enum WsClientError {
     #[error("unexpected response")]
     UnexpectedResponse,
 
+    /// Malformed packet
+    #[error(transparent)]
+    Codec(#[from] CodecError),
+
+    /// Unexpected websocket message, such as a text frame
+    #[error("unexpected message: {kind}")]
+    UnexpectedMessage {
+        /// Kind of the message
+        kind: &'static str,
+    },
+
     /// Decode message error
     #[error("decode message error")]
     Decode(#[from] DecodeError),
@@ -59,6 +70,14 @@ pub enum WsClientError {
         detail: Option<WsResponseErrorDetail>,
     },
 
+    /// A response or push is incorrectly signed, or a push is not signed
+    /// while required, see [`PacketSignature`](crate::PacketSignature)
+    #[error("invalid signature: command_code={command_code}")]
+    InvalidSignature {
+        /// Command code of the packet
+        command_code: u8,
+    },
+
     /// The request has been cancelled
     #[error("cancelled")]
     Cancelled,
@@ -69,7 +88,108 @@ pub enum WsClientError {
 
     /// Websocket error
     #[error(transparent)]
-    Websocket(#[from] tokio_tungstenite::tungstenite::Error),
+    Websocket(Box<tokio_tungstenite::tungstenite::Error>),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for WsClientError {
+    #[inline]
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        // boxed to keep `WsClientResult` small
+        Self::Websocket(Box::new(err))
+    }
+}
+
+/// Error of encoding or decoding a packet, see [`WsClientError::Codec`]
+///
+/// The offsets are counted from the beginning of the packet, including the
+/// header byte.
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    /// The packet is empty
+    #[error("empty packet")]
+    Empty,
+
+    /// The packet type in the header byte is unknown
+    #[error("unknown packet type: {ty}")]
+    UnknownPacketType {
+        /// Packet type
+        ty: u8,
+    },
+
+    /// The packet ends within its header
+    #[error("truncated header: type={ty} command_code={command_code:?} len={len}")]
+    TruncatedHeader {
+        /// Packet type
+        ty: u8,
+        /// Command code, if it could be read
+        command_code: Option<u8>,
+        /// Length of the packet
+        len: usize,
+    },
+
+    /// The body length exceeds the remaining bytes of the packet
+    #[error(
+        "truncated body: command_code={command_code} offset={offset} expected={expected} available={available}"
+    )]
+    TruncatedBody {
+        /// Command code
+        command_code: u8,
+        /// Offset of the body
+        offset: usize,
+        /// Body length in the header
+        expected: usize,
+        /// Remaining bytes of the packet
+        available: usize,
+    },
+
+    /// The packet ends within its signature
+    #[error("truncated signature: command_code={command_code} offset={offset}")]
+    TruncatedSignature {
+        /// Command code
+        command_code: u8,
+        /// Offset of the signature
+        offset: usize,
+    },
+
+    /// Unexpected bytes after the end of the packet
+    #[error("trailing bytes: command_code={command_code} offset={offset} len={len}")]
+    TrailingBytes {
+        /// Command code
+        command_code: u8,
+        /// Offset of the trailing bytes
+        offset: usize,
+        /// Number of the trailing bytes
+        len: usize,
+    },
+
+    /// The compressed body cannot be decompressed
+    #[error("invalid gzip body: command_code={command_code}: {source}")]
+    Gzip {
+        /// Command code
+        command_code: u8,
+        /// Decompression error
+        source: std::io::Error,
+    },
+
+    /// The decompressed body exceeds the maximum size allowed by the decoder
+    #[error("decompressed body too large: command_code={command_code} limit={limit}")]
+    DecompressedTooLarge {
+        /// Command code
+        command_code: u8,
+        /// Maximum size of a decompressed body
+        limit: usize,
+    },
+
+    /// The body is too large to be encoded
+    #[error("body too large: command_code={command_code} len={len} limit={limit}")]
+    BodyTooLarge {
+        /// Command code
+        command_code: u8,
+        /// Size of the body, once compressed if it is
+        len: usize,
+        /// Maximum size of an encoded body
+        limit: usize,
+    },
 }
 
 /// Websocket client result type
@@ -1,3 +1,5 @@
+use bytes::Bytes;
+
 use crate::WsClientError;
 
 /// The event type of Websocket client
@@ -10,6 +12,6 @@ pub enum WsEvent {
         /// Command code
         command_code: u8,
         /// Message body
-        body: Vec<u8>,
+        body: Bytes,
     },
 }
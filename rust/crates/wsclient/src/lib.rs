@@ -6,10 +6,19 @@
 #![warn(missing_docs)]
 
 mod client;
+#[cfg(feature = "codec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "codec")))]
+pub mod codec;
+#[cfg(not(feature = "codec"))]
 mod codec;
 mod error;
 mod event;
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub mod mock;
 
-pub use client::{CodecType, Platform, ProtocolVersion, RateLimit, WsClient, WsSession};
-pub use error::{WsClientError, WsClientResult, WsCloseReason, WsResponseErrorDetail};
+pub use client::{
+    CodecType, PacketSignature, Platform, ProtocolVersion, RateLimit, WsClient, WsSession,
+};
+pub use error::{CodecError, WsClientError, WsClientResult, WsCloseReason, WsResponseErrorDetail};
 pub use event::WsEvent;
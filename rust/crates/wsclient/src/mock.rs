@@ -0,0 +1,648 @@
+//! In-process mock of the LongPort Whale server for integration tests
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use longport_proto::{
+    control::{AuthRequest, AuthResponse, ReconnectRequest, ReconnectResponse},
+    trade::{Notification, Sub, SubResponse, Unsub, UnsubResponse},
+};
+use prost::Message as _;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tokio_tungstenite::{
+    tungstenite::{
+        handshake::derive_accept_key,
+        protocol::{frame::coding::CloseCode, CloseFrame, Role},
+        Message,
+    },
+    WebSocketStream,
+};
+
+use crate::codec::Packet;
+
+const COMMAND_CODE_AUTH: u8 = 2;
+const COMMAND_CODE_RECONNECT: u8 = 3;
+const COMMAND_CODE_SUBSCRIBE: u8 = 16;
+const COMMAND_CODE_UNSUBSCRIBE: u8 = 17;
+const COMMAND_CODE_PUSH_NOTIFICATION: u8 = 18;
+
+const SESSION_EXPIRES: Duration = Duration::from_secs(24 * 60 * 60);
+const MAX_HEADER_SIZE: usize = 16 * 1024;
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+type HttpHandler = Arc<dyn Fn(&HttpRequest) -> HttpResponse + Send + Sync>;
+
+/// An HTTP request received by the mock server, see
+/// [`MockServer::set_http_handler`]
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    /// Method, e.g. `GET`
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    /// Query string, empty if none
+    pub query: String,
+    /// Body
+    pub body: Vec<u8>,
+}
+
+/// Response of a handler set by [`MockServer::set_http_handler`]
+#[derive(Debug, Clone)]
+pub enum HttpResponse {
+    /// A successful response whose `data` field is the JSON `data`
+    Data(String),
+    /// An OpenAPI error response
+    Error {
+        /// Error code
+        code: i64,
+        /// Error message
+        message: String,
+    },
+    /// A response with the status code and an empty body, e.g. `429` for
+    /// `Too Many Requests`
+    Status(u16),
+}
+
+/// An error response scripted by [`MockServer::fail_next`]
+struct Failure {
+    status: u8,
+    code: u64,
+    msg: String,
+}
+
+struct Connection {
+    tx: mpsc::UnboundedSender<Message>,
+    authenticated: bool,
+    subscriptions: HashSet<String>,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    connections: HashMap<u64, Connection>,
+    sessions: HashSet<String>,
+    failures: HashMap<u8, VecDeque<Failure>>,
+    requests: HashMap<u8, usize>,
+    signature_key: Option<Vec<u8>>,
+    signed_requests: usize,
+    http_handlers: HashMap<String, HttpHandler>,
+    http_requests: HashMap<String, usize>,
+    refuse_connections: bool,
+}
+
+/// In-process mock of the LongPort Whale server
+///
+/// It serves the socket token API (`GET /v1/socket/token`) and the HTTP
+/// responses scripted with [`MockServer::set_http_response`] or
+/// [`MockServer::set_http_handler`], and the binary websocket protocol on the
+/// same address:
+///
+/// - auth (command `2`) accepts any token and creates a new session
+/// - reconnect (command `3`) accepts the sessions created by this server
+/// - subscribe (command `16`) and unsubscribe (command `17`) maintain the
+///   topics of each connection
+///
+/// The tests script the pushes, the forced disconnects and the error
+/// responses. The server is stopped when it is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use longportwhale_wscli::mock::MockServer;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let server = MockServer::start().await?;
+/// println!("{} {}", server.http_url(), server.ws_url());
+///
+/// server.push_order_changed(r#"{"order_id": "1"}"#);
+/// server.disconnect();
+/// # Ok::<_, std::io::Error>(())
+/// # });
+/// ```
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+        for (_, connection) in self.state.lock().unwrap().connections.drain() {
+            let _ = connection.tx.send(Message::Close(None));
+        }
+    }
+}
+
+impl MockServer {
+    /// Starts a server listening on a random port of `127.0.0.1`
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let handle = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(handle_connection(state.clone(), stream));
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            state,
+            handle,
+        })
+    }
+
+    /// Returns the url of the HTTP API, e.g. `http://127.0.0.1:12345`
+    pub fn http_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the url of the websocket endpoint, e.g. `ws://127.0.0.1:12345`
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Sends a push message to all the authenticated connections
+    pub fn push(&self, command_code: u8, body: Vec<u8>) {
+        self.send_push(command_code, body, |connection| connection.authenticated);
+    }
+
+    /// Sends an `order_changed_lb` notification to the connections
+    /// subscribed to the `private` topic
+    ///
+    /// `data` is the JSON object of the order, as pushed by the server.
+    pub fn push_order_changed(&self, data: &str) {
+        let body = Notification {
+            topic: "private".to_string(),
+            data: format!(r#"{{"event":"order_changed_lb","data":{data}}}"#).into_bytes(),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        self.send_push(COMMAND_CODE_PUSH_NOTIFICATION, body, |connection| {
+            connection.subscriptions.contains("private")
+        });
+    }
+
+    fn send_push(&self, command_code: u8, body: Vec<u8>, filter: impl Fn(&Connection) -> bool) {
+        let state = self.state.lock().unwrap();
+        let mut packet = Packet::Push {
+            command_code,
+            body: body.into(),
+            signature: None,
+        };
+        if let Some(key) = &state.signature_key {
+            packet
+                .sign(key, rand::random())
+                .expect("push body too large");
+        }
+        let msg = Message::Binary(packet.encode().expect("push body too large"));
+        for connection in state.connections.values() {
+            if filter(connection) {
+                let _ = connection.tx.send(msg.clone());
+            }
+        }
+    }
+
+    /// Closes all the connections with a close frame
+    ///
+    /// The sessions are kept, so the clients can reconnect with them.
+    pub fn disconnect(&self) {
+        for (_, connection) in self.state.lock().unwrap().connections.drain() {
+            let _ = connection.tx.send(Message::Close(Some(CloseFrame {
+                code: CloseCode::Away,
+                reason: "disconnected by mock server".into(),
+            })));
+        }
+    }
+
+    /// Rejects the new websocket connections with `503 Service Unavailable`
+    /// while `refuse` is `true`, the open connections are kept
+    pub fn refuse_connections(&self, refuse: bool) {
+        self.state.lock().unwrap().refuse_connections = refuse;
+    }
+
+    /// Invalidates all the sessions, so the next reconnect requests fail
+    pub fn expire_sessions(&self) {
+        self.state.lock().unwrap().sessions.clear();
+    }
+
+    /// Signs the responses and the pushes with `key`, and verifies the
+    /// signed requests, `None` disables the signatures
+    ///
+    /// A request with an invalid signature gets an error response with
+    /// status `1` and code `403`.
+    pub fn set_signature_key(&self, key: Option<impl Into<Vec<u8>>>) {
+        self.state.lock().unwrap().signature_key = key.map(Into::into);
+    }
+
+    /// Returns the number of correctly signed requests received so far
+    pub fn signed_requests(&self) -> usize {
+        self.state.lock().unwrap().signed_requests
+    }
+
+    /// Responds to the next request of the command with an error
+    ///
+    /// The failures of a command are used in the order they are scripted.
+    pub fn fail_next(&self, command_code: u8, status: u8, code: u64, msg: impl Into<String>) {
+        self.state
+            .lock()
+            .unwrap()
+            .failures
+            .entry(command_code)
+            .or_default()
+            .push_back(Failure {
+                status,
+                code,
+                msg: msg.into(),
+            });
+    }
+
+    /// Responds to the HTTP requests of `path` with a successful response
+    /// whose `data` field is the JSON `data`, whatever the method and the
+    /// query string
+    pub fn set_http_response(&self, path: impl Into<String>, data: impl Into<String>) {
+        let data = data.into();
+        self.set_http_handler(path, move |_| HttpResponse::Data(data.clone()));
+    }
+
+    /// Responds to the HTTP requests of `path` with the response returned by
+    /// `handler`
+    pub fn set_http_handler(
+        &self,
+        path: impl Into<String>,
+        handler: impl Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .http_handlers
+            .insert(path.into(), Arc::new(handler));
+    }
+
+    /// Returns the number of HTTP requests of `path` received so far
+    pub fn http_requests(&self, path: &str) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .http_requests
+            .get(path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of requests of the command received so far
+    pub fn requests(&self, command_code: u8) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .requests
+            .get(&command_code)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of open websocket connections
+    pub fn connections(&self) -> usize {
+        self.state.lock().unwrap().connections.len()
+    }
+
+    /// Returns the topics subscribed by the open connections
+    pub fn subscriptions(&self) -> HashSet<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .connections
+            .values()
+            .flat_map(|connection| connection.subscriptions.iter().cloned())
+            .collect()
+    }
+}
+
+async fn handle_connection(state: Arc<Mutex<State>>, mut stream: TcpStream) {
+    let mut buf = Vec::new();
+    let (request, body_offset, content_length, websocket_key) = loop {
+        let mut chunk = [0; 1024];
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+
+        let mut headers = [httparse::EMPTY_HEADER; 32];
+        let mut req = httparse::Request::new(&mut headers);
+        match req.parse(&buf) {
+            Ok(httparse::Status::Complete(body_offset)) => {
+                let header = |name: &str| {
+                    req.headers
+                        .iter()
+                        .find(|header| header.name.eq_ignore_ascii_case(name))
+                        .map(|header| header.value)
+                };
+                let websocket_key = header("sec-websocket-key").map(derive_accept_key);
+                let content_length = header("content-length")
+                    .and_then(|value| std::str::from_utf8(value).ok())
+                    .and_then(|value| value.trim().parse::<usize>().ok())
+                    .unwrap_or_default();
+                let (path, query) = req
+                    .path
+                    .unwrap_or_default()
+                    .split_once('?')
+                    .unwrap_or((req.path.unwrap_or_default(), ""));
+                let request = HttpRequest {
+                    method: req.method.unwrap_or_default().to_string(),
+                    path: path.to_string(),
+                    query: query.to_string(),
+                    body: Vec::new(),
+                };
+                break (request, body_offset, content_length, websocket_key);
+            }
+            Ok(httparse::Status::Partial) if buf.len() < MAX_HEADER_SIZE => {}
+            _ => return,
+        }
+    };
+
+    match websocket_key {
+        Some(_) if state.lock().unwrap().refuse_connections => {
+            let resp = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+            let _ = stream.write_all(resp.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        }
+        Some(accept_key) => {
+            let resp = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Accept: {accept_key}\r\n\r\n"
+            );
+            if stream.write_all(resp.as_bytes()).await.is_ok() {
+                let conn = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+                handle_websocket(state, conn).await;
+            }
+        }
+        None => {
+            if content_length > MAX_BODY_SIZE {
+                return;
+            }
+            buf.drain(..body_offset);
+            while buf.len() < content_length {
+                let mut chunk = [0; 1024];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+            buf.truncate(content_length);
+            let request = HttpRequest {
+                body: buf,
+                ..request
+            };
+
+            let handler = {
+                let mut state = state.lock().unwrap();
+                *state.http_requests.entry(request.path.clone()).or_default() += 1;
+                state.http_handlers.get(&request.path).cloned()
+            };
+            // the handler is called without the lock, so that it can use the server
+            let response = match request.path.as_str() {
+                "/v1/socket/token" => Some(HttpResponse::Data(r#"{"otp":"mock-otp"}"#.to_string())),
+                _ => handler.map(|handler| handler(&request)),
+            };
+            let (status, body) = match response {
+                Some(HttpResponse::Data(data)) => {
+                    (200, format!(r#"{{"code":0,"message":"","data":{data}}}"#))
+                }
+                Some(HttpResponse::Error { code, message }) => (
+                    200,
+                    serde_json::json!({ "code": code, "message": message }).to_string(),
+                ),
+                Some(HttpResponse::Status(status)) => (status, String::new()),
+                None => (404, r#"{"code":404,"message":"not found"}"#.to_string()),
+            };
+            let resp = format!(
+                "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                reason_phrase(status),
+                body.len()
+            );
+            let _ = stream.write_all(resp.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+async fn handle_websocket(state: Arc<Mutex<State>>, conn: WebSocketStream<TcpStream>) {
+    let (mut sink, mut stream) = conn.split();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let id = {
+        let mut state = state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.connections.insert(
+            id,
+            Connection {
+                tx,
+                authenticated: false,
+                subscriptions: HashSet::new(),
+            },
+        );
+        id
+    };
+
+    loop {
+        tokio::select! {
+            item = stream.next() => {
+                let data = match item {
+                    Some(Ok(Message::Binary(data))) => data,
+                    Some(Ok(Message::Ping(data))) => {
+                        let _ = sink.send(Message::Pong(data)).await;
+                        continue;
+                    }
+                    Some(Ok(Message::Close(_))) => {
+                        let _ = sink.send(Message::Close(None)).await;
+                        break;
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(_)) | None => break,
+                };
+                let Ok(request @ Packet::Request { command_code, request_id, .. }) = Packet::decode(data) else {
+                    break;
+                };
+                let (status, body) = handle_request(&state, id, &request);
+                let mut resp = Packet::Response {
+                    command_code,
+                    request_id,
+                    status,
+                    body: body.into(),
+                    signature: None,
+                };
+                if let Some(key) = &state.lock().unwrap().signature_key {
+                    resp.sign(key, rand::random())
+                        .expect("response body too large");
+                }
+                let data = resp.encode().expect("response body too large");
+                if sink.send(Message::Binary(data)).await.is_err() {
+                    break;
+                }
+            }
+            item = rx.recv() => {
+                match item {
+                    Some(msg @ Message::Close(_)) => {
+                        let _ = sink.send(msg).await;
+                        break;
+                    }
+                    Some(msg) => {
+                        if sink.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+
+    state.lock().unwrap().connections.remove(&id);
+}
+
+/// Returns the status and the body of the response
+fn handle_request(state: &Mutex<State>, id: u64, request: &Packet) -> (u8, Vec<u8>) {
+    let Packet::Request {
+        command_code, body, ..
+    } = request
+    else {
+        unreachable!("not a request")
+    };
+    let (command_code, body) = (*command_code, &body[..]);
+
+    let mut state = state.lock().unwrap();
+    *state.requests.entry(command_code).or_default() += 1;
+
+    let error = |status, code, msg: &str| {
+        (
+            status,
+            longport_proto::Error {
+                code,
+                msg: msg.to_string(),
+            }
+            .encode_to_vec(),
+        )
+    };
+
+    if let Some(key) = &state.signature_key {
+        match request.verify(key) {
+            Some(true) => state.signed_requests += 1,
+            Some(false) => return error(1, 403, "invalid signature"),
+            None => {}
+        }
+    }
+
+    if let Some(failure) = state
+        .failures
+        .get_mut(&command_code)
+        .and_then(VecDeque::pop_front)
+    {
+        return error(failure.status, failure.code, &failure.msg);
+    }
+
+    let State {
+        next_id,
+        connections,
+        sessions,
+        ..
+    } = &mut *state;
+    let Some(connection) = connections.get_mut(&id) else {
+        return error(1, 500, "connection closed");
+    };
+
+    match command_code {
+        COMMAND_CODE_AUTH => {
+            if AuthRequest::decode(body).is_err() {
+                return error(1, 400, "invalid request");
+            }
+            *next_id += 1;
+            let session_id = format!("mock-session-{next_id}");
+            sessions.insert(session_id.clone());
+            connection.authenticated = true;
+            let expires = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                + SESSION_EXPIRES;
+            (
+                0,
+                AuthResponse {
+                    session_id,
+                    expires: expires.as_millis() as i64,
+                    ..Default::default()
+                }
+                .encode_to_vec(),
+            )
+        }
+        COMMAND_CODE_RECONNECT => match ReconnectRequest::decode(body) {
+            Ok(req) if sessions.contains(&req.session_id) => {
+                connection.authenticated = true;
+                (
+                    0,
+                    ReconnectResponse {
+                        session_id: req.session_id,
+                        expires: SESSION_EXPIRES.as_millis() as i64,
+                        ..Default::default()
+                    }
+                    .encode_to_vec(),
+                )
+            }
+            Ok(_) => error(1, 401, "session expired"),
+            Err(_) => error(1, 400, "invalid request"),
+        },
+        _ if !connection.authenticated => error(1, 401, "unauthenticated"),
+        COMMAND_CODE_SUBSCRIBE => match Sub::decode(body) {
+            Ok(req) => {
+                connection.subscriptions.extend(req.topics.iter().cloned());
+                (
+                    0,
+                    SubResponse {
+                        success: req.topics,
+                        fail: vec![],
+                        current: connection.subscriptions.iter().cloned().collect(),
+                    }
+                    .encode_to_vec(),
+                )
+            }
+            Err(_) => error(1, 400, "invalid request"),
+        },
+        COMMAND_CODE_UNSUBSCRIBE => match Unsub::decode(body) {
+            Ok(req) => {
+                for topic in &req.topics {
+                    connection.subscriptions.remove(topic);
+                }
+                (
+                    0,
+                    UnsubResponse {
+                        current: connection.subscriptions.iter().cloned().collect(),
+                    }
+                    .encode_to_vec(),
+                )
+            }
+            Err(_) => error(1, 400, "invalid request"),
+        },
+        _ => error(1, 404, "unknown command"),
+    }
+}
@@ -1,9 +1,12 @@
 use std::{sync::Arc, thread};
 
 use futures_util::{future::BoxFuture, Future};
-use tokio::sync::mpsc;
 
-use crate::{blocking::BlockingError, Result};
+use crate::{
+    blocking::BlockingError,
+    trade::{PushEvent, PushEventReceiver},
+    Result,
+};
 
 const THREAD_NAME: &str = "longportwhale-sync-runtime";
 
@@ -17,29 +20,18 @@ impl<Ctx> BlockingRuntime<Ctx>
 where
     Ctx: Send + Sync + 'static,
 {
-    pub(crate) fn try_new<CreateCtx, CreateCtxFut, PushType, PushCallback>(
+    pub(crate) fn try_new<CreateCtx, CreateCtxFut, PushCallback>(
         create_ctx: CreateCtx,
         mut push_callback: PushCallback,
     ) -> Result<Self>
     where
         CreateCtx: FnOnce() -> CreateCtxFut + Send + 'static,
-        CreateCtxFut: Future<Output = Result<(Ctx, mpsc::UnboundedReceiver<PushType>)>>,
-        PushCallback: FnMut(PushType) + Send + 'static,
-        PushType: Send + 'static,
+        CreateCtxFut: Future<Output = Result<(Ctx, PushEventReceiver)>>,
+        PushCallback: FnMut(PushEvent) + Send + 'static,
     {
         let (init_tx, init_rx) = flume::unbounded();
         let (task_tx, task_rx) = flume::unbounded::<ExecFn<Ctx>>();
 
-        // create push thread
-        let (push_tx, push_rx) = std::sync::mpsc::channel::<PushType>();
-        thread::Builder::new()
-            .spawn(move || {
-                while let Ok(event) = push_rx.recv() {
-                    push_callback(event);
-                }
-            })
-            .expect("spawn thread");
-
         // create a thread to execute the future
         thread::Builder::new()
             .name(THREAD_NAME.to_string())
@@ -51,7 +43,7 @@ where
                 let handle = rt.handle().clone();
 
                 rt.block_on(async move {
-                    let (ctx, mut event_rx) = match create_ctx().await {
+                    let (ctx, mut push_rx) = match create_ctx().await {
                         Ok(res) => {
                             let _ = init_tx.send(Ok(()));
                             res
@@ -64,23 +56,20 @@ where
                     };
                     let ctx = Arc::new(ctx);
 
-                    loop {
-                        tokio::select! {
-                            item = task_rx.recv_async() => {
-                                match item {
-                                    Ok(f) => {
-                                        handle.spawn(f(ctx.clone()));
-                                    },
-                                    Err(_) => break,
-                                }
-                            }
-                            item = event_rx.recv() => {
-                                match item {
-                                    Some(event) => _ = push_tx.send(event),
-                                    None => break,
-                                }
+                    // the push thread receives the events directly, so that
+                    // the capacity of the push channel is respected
+                    thread::Builder::new()
+                        .spawn(move || {
+                            while let Some(event) = push_rx.blocking_recv() {
+                                push_callback(event);
                             }
-                        }
+                        })
+                        .expect("spawn thread");
+
+                    // keep serving the calls after the context is closed, so
+                    // that they fail with the error of the context
+                    while let Ok(f) = task_rx.recv_async().await {
+                        handle.spawn(f(ctx.clone()));
                     }
                 });
             })
@@ -2,8 +2,15 @@ use std::sync::Arc;
 
 use crate::{
     blocking::runtime::BlockingRuntime,
-    trade::{PushEvent, TopicType, TradeContext},
-    Config, Result,
+    trade::{
+        AccountBalance, CashFlow, CashFlowOptions, ConnectionState,
+        EstimateMaxPurchaseQuantityOptions, EstimateMaxPurchaseQuantityResponse, EventReceiver,
+        Execution, FundPositionChannel, GetHistoryExecutionsOptions, GetHistoryOrdersOptions,
+        GetTodayExecutionsOptions, GetTodayOrdersOptions, MarginRatio, Order, PushEvent,
+        ReplaceOrderOptions, StockPositionChannel, SubmitOrderOptions, SubmitOrderResponse,
+        SubscribeEventsOptions, TopicType, TradeContext,
+    },
+    Config, Result, Symbol,
 };
 
 /// Trade context
@@ -21,6 +28,36 @@ impl TradeContextSync {
         Ok(Self { rt })
     }
 
+    /// Gracefully close the trade context, see [`TradeContext::close`]
+    pub fn close(&self) -> Result<()> {
+        self.rt.call(move |ctx| async move {
+            ctx.close().await;
+            Ok(())
+        })
+    }
+
+    /// Returns the current connection state
+    pub fn state(&self) -> Result<ConnectionState> {
+        self.rt.call(move |ctx| async move { Ok(ctx.state()) })
+    }
+
+    /// Returns the number of push events dropped because of the
+    /// [`OverflowPolicy`](crate::OverflowPolicy), see
+    /// [`TradeContext::dropped_events`]
+    pub fn dropped_events(&self) -> Result<u64> {
+        self.rt
+            .call(move |ctx| async move { Ok(ctx.dropped_events()) })
+    }
+
+    /// Subscribe to the push events with filters, see
+    /// [`TradeContext::subscribe_events`]
+    ///
+    /// Use [`EventReceiver::blocking_recv`] to receive the events.
+    pub fn subscribe_events(&self, opts: SubscribeEventsOptions) -> Result<EventReceiver> {
+        self.rt
+            .call(move |ctx| async move { Ok(ctx.subscribe_events(opts)) })
+    }
+
     /// Subscribe topics
     pub fn subscribe<I>(&self, topics: I) -> Result<()>
     where
@@ -38,4 +75,147 @@ impl TradeContextSync {
         self.rt
             .call(move |ctx| async move { ctx.unsubscribe(topics).await })
     }
+
+    /// Submit order
+    pub fn submit_order(&self, options: SubmitOrderOptions) -> Result<SubmitOrderResponse> {
+        self.rt
+            .call(move |ctx| async move { ctx.submit_order(options).await })
+    }
+
+    /// Submit a batch of orders
+    pub fn submit_orders(
+        &self,
+        orders: Vec<SubmitOrderOptions>,
+    ) -> Result<Vec<Result<SubmitOrderResponse>>> {
+        self.rt
+            .call(move |ctx| async move { Ok(ctx.submit_orders(orders).await) })
+    }
+
+    /// Cancel order
+    pub fn cancel_order(
+        &self,
+        account_no: impl Into<String>,
+        order_id: impl Into<String>,
+    ) -> Result<()> {
+        let account_no = account_no.into();
+        let order_id = order_id.into();
+        self.rt
+            .call(move |ctx| async move { ctx.cancel_order(account_no, order_id).await })
+    }
+
+    /// Cancel a batch of orders
+    pub fn cancel_orders<I, A, O>(&self, orders: I) -> Result<Vec<Result<()>>>
+    where
+        I: IntoIterator<Item = (A, O)>,
+        A: Into<String>,
+        O: Into<String>,
+    {
+        let orders = orders
+            .into_iter()
+            .map(|(account_no, order_id)| (account_no.into(), order_id.into()))
+            .collect::<Vec<(String, String)>>();
+        self.rt
+            .call(move |ctx| async move { Ok(ctx.cancel_orders(orders).await) })
+    }
+
+    /// Replace order
+    pub fn replace_order(&self, options: ReplaceOrderOptions) -> Result<()> {
+        self.rt
+            .call(move |ctx| async move { ctx.replace_order(options).await })
+    }
+
+    /// Get today orders
+    pub fn today_orders(&self, options: GetTodayOrdersOptions) -> Result<Vec<Order>> {
+        self.rt
+            .call(move |ctx| async move { ctx.today_orders(options).await })
+    }
+
+    /// Get history orders
+    pub fn history_orders(&self, options: GetHistoryOrdersOptions) -> Result<Vec<Order>> {
+        self.rt
+            .call(move |ctx| async move { ctx.history_orders(options).await })
+    }
+
+    /// Get order detail
+    pub fn order_detail(
+        &self,
+        account_no: impl Into<String>,
+        order_id: impl Into<String>,
+    ) -> Result<Order> {
+        let account_no = account_no.into();
+        let order_id = order_id.into();
+        self.rt
+            .call(move |ctx| async move { ctx.order_detail(account_no, order_id).await })
+    }
+
+    /// Get today executions
+    pub fn today_executions(&self, options: GetTodayExecutionsOptions) -> Result<Vec<Execution>> {
+        self.rt
+            .call(move |ctx| async move { ctx.today_executions(options).await })
+    }
+
+    /// Get history executions
+    pub fn history_executions(
+        &self,
+        options: GetHistoryExecutionsOptions,
+    ) -> Result<Vec<Execution>> {
+        self.rt
+            .call(move |ctx| async move { ctx.history_executions(options).await })
+    }
+
+    /// Get account balance
+    pub fn account_balance(
+        &self,
+        account_no: impl Into<String>,
+        currency: impl Into<String>,
+    ) -> Result<AccountBalance> {
+        let account_no = account_no.into();
+        let currency = currency.into();
+        self.rt
+            .call(move |ctx| async move { ctx.account_balance(account_no, currency).await })
+    }
+
+    /// Get stock positions
+    pub fn stock_positions(
+        &self,
+        account_no: impl Into<String>,
+        symbols: impl IntoIterator<Item = Symbol>,
+    ) -> Result<Vec<StockPositionChannel>> {
+        let account_no = account_no.into();
+        let symbols = symbols.into_iter().collect::<Vec<_>>();
+        self.rt
+            .call(move |ctx| async move { ctx.stock_positions(account_no, symbols).await })
+    }
+
+    /// Get fund positions
+    pub fn fund_positions(
+        &self,
+        account_no: impl Into<String>,
+    ) -> Result<Vec<FundPositionChannel>> {
+        let account_no = account_no.into();
+        self.rt
+            .call(move |ctx| async move { ctx.fund_positions(account_no).await })
+    }
+
+    /// Get cash flow
+    pub fn cash_flow(&self, options: CashFlowOptions) -> Result<Vec<CashFlow>> {
+        self.rt
+            .call(move |ctx| async move { ctx.cash_flow(options).await })
+    }
+
+    /// Estimating the maximum purchase quantity for Hong Kong and US stocks,
+    /// warrants, and options
+    pub fn estimate_max_purchase_quantity(
+        &self,
+        options: EstimateMaxPurchaseQuantityOptions,
+    ) -> Result<EstimateMaxPurchaseQuantityResponse> {
+        self.rt
+            .call(move |ctx| async move { ctx.estimate_max_purchase_quantity(options).await })
+    }
+
+    /// Get margin ratio
+    pub fn margin_ratio(&self, symbol: Symbol) -> Result<MarginRatio> {
+        self.rt
+            .call(move |ctx| async move { ctx.margin_ratio(symbol).await })
+    }
 }
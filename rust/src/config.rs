@@ -1,9 +1,13 @@
+use std::{fmt, path::PathBuf, sync::Arc, time::Duration};
+
 pub(crate) use http::{header, HeaderValue, Request};
 use longportwhale_httpcli::{HttpClient, HttpClientConfig};
+use longportwhale_wscli::{PacketSignature, WsSession};
 use num_enum::IntoPrimitive;
+use rand::Rng;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
 const TRADE_WS_URL: &str = "wss://openapi-trade.longportapp.com";
 
@@ -30,12 +34,271 @@ impl Language {
     }
 }
 
+type GiveUpCallback = Arc<dyn Fn(&Error) + Send + Sync>;
+
+/// Reconnect policy of the trade context
+///
+/// The delay before the `n`th reconnect attempt is `initial_delay *
+/// multiplier^(n - 1)`, capped at `max_delay`, and then randomly shifted by up
+/// to `jitter` of itself, so that clients disconnected at the same time do not
+/// reconnect in lockstep.
+#[derive(Clone)]
+pub struct ReconnectPolicy {
+    initial_delay: Duration,
+    multiplier: f64,
+    max_delay: Duration,
+    jitter: f64,
+    max_attempts: Option<u32>,
+    on_give_up: Option<GiveUpCallback>,
+}
+
+impl fmt::Debug for ReconnectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectPolicy")
+            .field("initial_delay", &self.initial_delay)
+            .field("multiplier", &self.multiplier)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("max_attempts", &self.max_attempts)
+            .finish()
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(2),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(60),
+            jitter: 0.2,
+            max_attempts: None,
+            on_give_up: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Specifies the delay before the first reconnect attempt
+    ///
+    /// Default: `2s`
+    #[inline]
+    #[must_use]
+    pub fn initial_delay(self, initial_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            ..self
+        }
+    }
+
+    /// Specifies the factor the delay is multiplied by after each attempt
+    ///
+    /// Default: `2.0`
+    #[inline]
+    #[must_use]
+    pub fn multiplier(self, multiplier: f64) -> Self {
+        Self {
+            multiplier: multiplier.max(1.0),
+            ..self
+        }
+    }
+
+    /// Specifies the maximum delay between two attempts
+    ///
+    /// Default: `60s`
+    #[inline]
+    #[must_use]
+    pub fn max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    /// Specifies the jitter as a fraction of the delay, between `0.0` and
+    /// `1.0`
+    ///
+    /// Default: `0.2`
+    #[inline]
+    #[must_use]
+    pub fn jitter(self, jitter: f64) -> Self {
+        Self {
+            jitter: jitter.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Specifies the maximum number of consecutive reconnect attempts
+    ///
+    /// When all attempts failed, the trade context is closed, the push event
+    /// receiver gets a [`ConnectionState::Closed`](crate::trade::ConnectionState::Closed)
+    /// event with [`Error::ReconnectGaveUp`], and the pending requests fail
+    /// with the same error.
+    ///
+    /// Default: unlimited
+    #[inline]
+    #[must_use]
+    pub fn max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: Some(max_attempts),
+            ..self
+        }
+    }
+
+    /// Specifies a callback that is called when the trade context gives up
+    /// reconnecting
+    #[inline]
+    #[must_use]
+    pub fn on_give_up(self, f: impl Fn(&Error) + Send + Sync + 'static) -> Self {
+        Self {
+            on_give_up: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Returns `true` if no more attempts are allowed after `attempts` failed
+    /// attempts
+    pub(crate) fn exhausted(&self, attempts: u32) -> bool {
+        matches!(self.max_attempts, Some(max_attempts) if attempts >= max_attempts)
+    }
+
+    /// Returns the delay before the next attempt after `attempts` failed
+    /// attempts
+    pub(crate) fn delay(&self, attempts: u32) -> Duration {
+        let delay = (self.initial_delay.as_secs_f64()
+            * self.multiplier.powi(attempts.min(i32::MAX as u32) as i32))
+        .min(self.max_delay.as_secs_f64());
+        let jitter = if self.jitter > 0.0 {
+            delay * rand::thread_rng().gen_range(-self.jitter..=self.jitter)
+        } else {
+            0.0
+        };
+        // the jitter may push a `max_delay` close to `Duration::MAX` beyond it
+        Duration::try_from_secs_f64((delay + jitter).max(0.0)).unwrap_or(self.max_delay)
+    }
+
+    pub(crate) fn give_up(&self, err: &Error) {
+        if let Some(on_give_up) = &self.on_give_up {
+            on_give_up(err);
+        }
+    }
+}
+
+/// What the trade context does when the push event channel is full, see
+/// [`Config::push_capacity`]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// Wait until the receiver has room, no more messages are read from the
+    /// server meanwhile. The connection state events never wait.
+    #[default]
+    Block,
+    /// Drop the oldest buffered event other than a connection state event to
+    /// make room for the new one
+    DropOldest,
+    /// Drop the new event and log an [`Error::PushChannelFull`], the trade
+    /// context stays open
+    FailFast,
+}
+
+/// Journal of the raw push messages received by the trade context, see
+/// [`Config::journal`]
+///
+/// The journal is appended to the file at `path`. When it grows beyond
+/// `max_file_size`, it is renamed to `path.1`, the previous `path.1` to
+/// `path.2` and so on, and the oldest file beyond `max_files` is removed.
+#[derive(Debug, Clone)]
+pub struct JournalConfig {
+    pub(crate) path: PathBuf,
+    pub(crate) max_file_size: u64,
+    pub(crate) max_files: usize,
+    pub(crate) capacity: usize,
+    pub(crate) overflow_policy: OverflowPolicy,
+}
+
+impl JournalConfig {
+    /// Create a new `JournalConfig` writing to the file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_file_size: 64 * 1024 * 1024,
+            max_files: 8,
+            capacity: 1024,
+            overflow_policy: OverflowPolicy::Block,
+        }
+    }
+
+    /// Specifies the size in bytes beyond which the journal file is rotated
+    ///
+    /// Default: `64MiB`
+    #[inline]
+    #[must_use]
+    pub fn max_file_size(self, max_file_size: u64) -> Self {
+        Self {
+            max_file_size,
+            ..self
+        }
+    }
+
+    /// Specifies the number of rotated files that are kept
+    ///
+    /// Default: `8`
+    #[inline]
+    #[must_use]
+    pub fn max_files(self, max_files: usize) -> Self {
+        Self { max_files, ..self }
+    }
+
+    /// Specifies the number of messages waiting to be written, and what to do
+    /// when the writer falls behind
+    ///
+    /// The messages dropped because of the policy are only missing from the
+    /// journal, the push events are still sent, and an error is logged with
+    /// [`OverflowPolicy::FailFast`].
+    ///
+    /// Default: `1024` with [`OverflowPolicy::Block`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    #[must_use]
+    pub fn capacity(self, capacity: usize, policy: OverflowPolicy) -> Self {
+        assert!(capacity > 0, "journal capacity must be greater than 0");
+        Self {
+            capacity,
+            overflow_policy: policy,
+            ..self
+        }
+    }
+}
+
+/// Key of the signatures of the websocket packets, see
+/// [`Config::packet_signature`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SignatureKey {
+    /// The app secret, all the packets are signed
+    AppSecret,
+    /// The session id, the packets are signed once authenticated
+    Session,
+}
+
+/// Default capacity of the command channel of the trade context
+const COMMAND_CAPACITY: usize = 64;
+
+/// Default number of events buffered for the subscribers of the trade context
+const EVENTS_CAPACITY: usize = 1024;
+
 /// Configuration options for LongPort sdk
 #[derive(Debug, Clone)]
 pub struct Config {
     pub(crate) http_cli_config: HttpClientConfig,
     pub(crate) trade_ws_url: String,
     pub(crate) language: Language,
+    pub(crate) recover_orders: Vec<String>,
+    pub(crate) reconnect_policy: ReconnectPolicy,
+    pub(crate) push_capacity: Option<usize>,
+    pub(crate) overflow_policy: OverflowPolicy,
+    pub(crate) command_capacity: usize,
+    pub(crate) events_capacity: usize,
+    pub(crate) journal: Option<JournalConfig>,
+    pub(crate) request_gzip_threshold: Option<usize>,
+    pub(crate) signature_key: Option<SignatureKey>,
+    pub(crate) reject_unsigned_pushes: bool,
 }
 
 impl Config {
@@ -49,6 +312,16 @@ impl Config {
             http_cli_config: HttpClientConfig::new(app_key, app_secret, access_token),
             trade_ws_url: TRADE_WS_URL.to_string(),
             language: Language::EN,
+            recover_orders: Vec::new(),
+            reconnect_policy: ReconnectPolicy::default(),
+            push_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            command_capacity: COMMAND_CAPACITY,
+            events_capacity: EVENTS_CAPACITY,
+            journal: None,
+            request_gzip_threshold: None,
+            signature_key: None,
+            reject_unsigned_pushes: false,
         }
     }
 
@@ -76,6 +349,16 @@ impl Config {
             http_cli_config,
             trade_ws_url,
             language: Language::EN,
+            recover_orders: Vec::new(),
+            reconnect_policy: ReconnectPolicy::default(),
+            push_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            command_capacity: COMMAND_CAPACITY,
+            events_capacity: EVENTS_CAPACITY,
+            journal: None,
+            request_gzip_threshold: None,
+            signature_key: None,
+            reject_unsigned_pushes: false,
         })
     }
 
@@ -110,6 +393,170 @@ impl Config {
         Self { language, ..self }
     }
 
+    /// Enables the recovery of order updates missed during a reconnect for the
+    /// specified accounts
+    ///
+    /// After the trade context is reconnected and authenticated, today's
+    /// orders of these accounts are fetched, and a
+    /// [`PushOrderChanged`](crate::trade::PushOrderChanged) event with
+    /// `recovered` set to `true` is sent for each order that has changed since
+    /// the last event received for it.
+    ///
+    /// Default: disabled
+    #[must_use]
+    pub fn recover_orders(self, account_nos: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            recover_orders: account_nos.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Specifies the reconnect policy of the trade context
+    ///
+    /// Default: `ReconnectPolicy::default()`
+    #[must_use]
+    pub fn reconnect_policy(self, reconnect_policy: ReconnectPolicy) -> Self {
+        Self {
+            reconnect_policy,
+            ..self
+        }
+    }
+
+    /// Specifies the capacity of the push event channel of the trade context,
+    /// and what to do when it is full
+    ///
+    /// The number of events dropped because of the policy is returned by
+    /// [`TradeContext::dropped_events`](crate::trade::TradeContext::dropped_events).
+    /// The connection state events are not counted in the capacity, they
+    /// never wait for the receiver nor take the room of another event.
+    ///
+    /// Default: unbounded
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    #[must_use]
+    pub fn push_capacity(self, capacity: usize, policy: OverflowPolicy) -> Self {
+        assert!(capacity > 0, "push capacity must be greater than 0");
+        Self {
+            push_capacity: Some(capacity),
+            overflow_policy: policy,
+            ..self
+        }
+    }
+
+    /// Specifies the capacity of the command channel of the trade context
+    ///
+    /// The calls that send commands to the background task, such as
+    /// [`TradeContext::subscribe`](crate::trade::TradeContext::subscribe),
+    /// wait when the channel is full.
+    ///
+    /// Default: `64`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    #[must_use]
+    pub fn command_capacity(self, capacity: usize) -> Self {
+        assert!(capacity > 0, "command capacity must be greater than 0");
+        Self {
+            command_capacity: capacity,
+            ..self
+        }
+    }
+
+    /// Specifies the number of events buffered for the subscribers created by
+    /// [`TradeContext::subscribe_events`](crate::trade::TradeContext::subscribe_events)
+    ///
+    /// A subscriber that falls behind by more events lags, see
+    /// [`LagPolicy`](crate::trade::LagPolicy).
+    ///
+    /// Default: `1024`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    #[must_use]
+    pub fn events_capacity(self, capacity: usize) -> Self {
+        assert!(capacity > 0, "events capacity must be greater than 0");
+        Self {
+            events_capacity: capacity,
+            ..self
+        }
+    }
+
+    /// Enables the journal of the raw push messages received by the trade
+    /// context, which can be replayed with
+    /// [`ReplayContext`](crate::trade::ReplayContext)
+    ///
+    /// The messages are written by a background thread, a failure to write
+    /// or to queue a message is only logged and never affects the push
+    /// events. See [`JournalConfig::capacity`] for what the trade context does
+    /// when the thread falls behind.
+    ///
+    /// Default: disabled
+    #[must_use]
+    pub fn journal(self, journal: JournalConfig) -> Self {
+        Self {
+            journal: Some(journal),
+            ..self
+        }
+    }
+
+    /// Compresses the bodies of the websocket requests of at least
+    /// `threshold` bytes with gzip
+    ///
+    /// Default: disabled
+    #[must_use]
+    pub fn request_gzip_threshold(self, threshold: usize) -> Self {
+        Self {
+            request_gzip_threshold: Some(threshold),
+            ..self
+        }
+    }
+
+    /// Signs the websocket requests with the key, and verifies the
+    /// signatures of the responses and pushes
+    ///
+    /// A response or push that is incorrectly signed closes the connection
+    /// with [`WsClientError::InvalidSignature`](crate::wsclient::WsClientError::InvalidSignature),
+    /// and the trade context reconnects.
+    ///
+    /// Default: disabled
+    #[must_use]
+    pub fn packet_signature(self, key: SignatureKey) -> Self {
+        Self {
+            signature_key: Some(key),
+            ..self
+        }
+    }
+
+    /// Rejects the pushes that are not signed, like the incorrectly signed
+    /// ones, requires [`Config::packet_signature`]
+    ///
+    /// Default: `false`
+    #[must_use]
+    pub fn reject_unsigned_pushes(self, reject_unsigned_pushes: bool) -> Self {
+        Self {
+            reject_unsigned_pushes,
+            ..self
+        }
+    }
+
+    /// Returns the signature settings of the websocket client, `session` is
+    /// the current session if authenticated
+    pub(crate) fn create_packet_signature(
+        &self,
+        session: Option<&WsSession>,
+    ) -> Option<PacketSignature> {
+        let key = match (self.signature_key?, session) {
+            (SignatureKey::AppSecret, _) => self.http_cli_config.app_secret().as_bytes().to_vec(),
+            (SignatureKey::Session, Some(session)) => session.session_id.as_bytes().to_vec(),
+            (SignatureKey::Session, None) => return None,
+        };
+        Some(PacketSignature::new(key).reject_unsigned_pushes(self.reject_unsigned_pushes))
+    }
+
     /// Create http client use the http client config
     pub fn create_http_client(&self) -> HttpClient {
         HttpClient::new(self.http_cli_config.clone())
@@ -132,3 +579,38 @@ impl Config {
         self.create_ws_request(&self.trade_ws_url)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_delay() {
+        let policy = ReconnectPolicy::default()
+            .initial_delay(Duration::from_secs(1))
+            .multiplier(2.0)
+            .max_delay(Duration::from_secs(10))
+            .jitter(0.0)
+            .max_attempts(5);
+
+        let delays = (0..6)
+            .map(|n| policy.delay(n).as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 8, 10, 10]);
+        assert!(policy.delay(u32::MAX) == Duration::from_secs(10));
+        assert!(!policy.exhausted(4));
+        assert!(policy.exhausted(5));
+
+        let policy = policy.jitter(0.5);
+        for _ in 0..100 {
+            let delay = policy.delay(2);
+            assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(6));
+        }
+
+        // the jitter never overflows the delay
+        let policy = policy.max_delay(Duration::MAX);
+        for _ in 0..100 {
+            assert!(policy.delay(u32::MAX) >= Duration::MAX / 2);
+        }
+    }
+}
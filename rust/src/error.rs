@@ -36,6 +36,46 @@ pub enum Error {
         symbol: String,
     },
 
+    /// Missing a field required by the request options
+    #[error("missing field `{name}` required by {required_by}")]
+    MissingField {
+        /// Field name
+        name: &'static str,
+
+        /// What requires the field, e.g. the order type
+        required_by: String,
+    },
+
+    /// A field of the request options has an invalid value
+    #[error("invalid field `{name}`: {reason}")]
+    InvalidField {
+        /// Field name
+        name: &'static str,
+
+        /// Why the value is invalid
+        reason: String,
+    },
+
+    /// The trade context gave up reconnecting, see
+    /// [`ReconnectPolicy::max_attempts`](crate::ReconnectPolicy::max_attempts)
+    #[error("gave up reconnecting after {attempts} attempts")]
+    ReconnectGaveUp {
+        /// Number of failed reconnect attempts
+        attempts: u32,
+    },
+
+    /// The push event channel is full, see
+    /// [`OverflowPolicy::FailFast`](crate::OverflowPolicy::FailFast)
+    #[error("push event channel is full, capacity: {capacity}")]
+    PushChannelFull {
+        /// Capacity of the channel
+        capacity: usize,
+    },
+
+    /// IO error, e.g. when reading or writing the push journal
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     /// HTTP client error
     #[error(transparent)]
     HttpClient(#[from] HttpClientError),
@@ -75,7 +115,12 @@ impl Error {
             | Error::DecodeJSON(_)
             | Error::InvalidSecuritySymbol { .. }
             | Error::ParseField { .. }
+            | Error::MissingField { .. }
+            | Error::InvalidField { .. }
             | Error::UnknownCommand(_)
+            | Error::ReconnectGaveUp { .. }
+            | Error::PushChannelFull { .. }
+            | Error::Io(_)
             | Error::HttpClient(_)
             | Error::WsClient(_) => SimpleError::Other(self.to_string()),
             #[cfg(feature = "blocking")]
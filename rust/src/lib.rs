@@ -17,9 +17,10 @@ mod types;
 pub mod blocking;
 pub mod trade;
 
-pub use config::{Config, Language};
+pub use config::{Config, JournalConfig, Language, OverflowPolicy, ReconnectPolicy, SignatureKey};
 pub use error::{Error, Result, SimpleError};
 pub use longportwhale_httpcli as httpclient;
+pub use longportwhale_wscli as wsclient;
 pub use rust_decimal::Decimal;
 pub use trade::TradeContext;
-pub use types::Market;
+pub use types::{Market, Symbol};
@@ -177,16 +177,29 @@ pub(crate) mod trigger_status {
     }
 }
 
+pub(crate) mod symbol_lenient {
+    use super::*;
+    use crate::Symbol;
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Symbol, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Symbol::parse_lenient(String::deserialize(deserializer)?))
+    }
+}
+
 pub(crate) mod symbol_opt {
     use super::*;
+    use crate::Symbol;
 
-    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<Symbol>, D::Error>
     where
         D: Deserializer<'de>,
     {
         match <Option<String>>::deserialize(deserializer)? {
             Some(value) if value.is_empty() => Ok(None),
-            Some(value) => Ok(Some(value)),
+            Some(value) => Ok(Some(Symbol::parse_lenient(value))),
             _ => Ok(None),
         }
     }
@@ -230,3 +243,64 @@ pub(crate) mod int64_str_empty_is_none {
         }
     }
 }
+
+pub(crate) mod outside_rth {
+    use super::*;
+    use crate::trade::OutsideRTH;
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<OutsideRTH>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        match value.as_str() {
+            "" | "UnknownOutsideRth" => Ok(None),
+            _ => Ok(Some(
+                OutsideRTH::from_str(value.as_str()).unwrap_or_default(),
+            )),
+        }
+    }
+}
+
+/// A value that the server sends either as a JSON string or as a JSON integer
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StrOrInt {
+    Int(i64),
+    Str(String),
+}
+
+pub(crate) mod risk_level {
+    use super::*;
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<i32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match StrOrInt::deserialize(deserializer)? {
+            StrOrInt::Int(value) => {
+                i32::try_from(value).map_err(|err| D::Error::custom(err.to_string()))
+            }
+            StrOrInt::Str(value) if !value.is_empty() => value
+                .parse::<i32>()
+                .map_err(|err| D::Error::custom(err.to_string())),
+            StrOrInt::Str(_) => Ok(0),
+        }
+    }
+}
+
+pub(crate) mod enum_str_or_int {
+    use super::*;
+
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Default,
+    {
+        let value = match StrOrInt::deserialize(deserializer)? {
+            StrOrInt::Int(value) => value.to_string(),
+            StrOrInt::Str(value) => value,
+        };
+        Ok(T::from_str(&value).unwrap_or_default())
+    }
+}
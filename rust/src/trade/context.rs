@@ -1,31 +1,137 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
+use futures_util::{stream, StreamExt};
+use longportwhale_httpcli::{HttpClient, Json, Method};
 use longportwhale_wscli::WsClientError;
-use tokio::sync::{mpsc, oneshot};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
 use crate::{
     trade::{
         core::{Command, Core},
-        PushEvent, TopicType,
+        events::PushSender,
+        AccountBalance, CashFlow, CashFlowOptions, ConnectionState,
+        EstimateMaxPurchaseQuantityOptions, EstimateMaxPurchaseQuantityResponse, EventReceiver,
+        Execution, FundPositionChannel, GetHistoryExecutionsOptions, GetHistoryOrdersOptions,
+        GetTodayExecutionsOptions, GetTodayOrdersOptions, MarginRatio, Order, PushEvent,
+        PushEventReceiver, ReplaceOrderOptions, StockPositionChannel, SubmitOrderOptions,
+        SubscribeEventsOptions, TopicType,
     },
-    Config, Result,
+    Config, Result, Symbol,
 };
 
+/// Maximum number of requests in flight for the batch APIs
+const BATCH_CONCURRENCY: usize = 5;
+
+/// Response for submit order request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitOrderResponse {
+    /// Order id
+    pub order_id: String,
+}
+
 /// Trade context
 #[derive(Clone)]
 pub struct TradeContext {
-    command_tx: mpsc::UnboundedSender<Command>,
+    command_tx: mpsc::Sender<Command>,
+    state_rx: watch::Receiver<ConnectionState>,
+    events_tx: broadcast::WeakSender<PushEvent>,
+    dropped: Arc<AtomicU64>,
+    http_cli: HttpClient,
 }
 
 impl TradeContext {
     /// Create a `TradeContext`
-    pub async fn try_new(
-        config: Arc<Config>,
-    ) -> Result<(Self, mpsc::UnboundedReceiver<PushEvent>)> {
-        let (command_tx, command_rx) = mpsc::unbounded_channel();
-        let (push_tx, push_rx) = mpsc::unbounded_channel();
-        tokio::spawn(Core::try_new(config, command_rx, push_tx).await?.run());
-        Ok((TradeContext { command_tx }, push_rx))
+    pub async fn try_new(config: Arc<Config>) -> Result<(Self, PushEventReceiver)> {
+        let http_cli = config.create_http_client();
+        let (command_tx, command_rx) = mpsc::channel(config.command_capacity);
+        let (push_tx, push_rx) = PushSender::new(&config);
+        let events_tx = push_tx.events_tx();
+        let dropped = push_tx.dropped();
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connecting);
+        tokio::spawn(
+            Core::try_new(config, http_cli.clone(), command_rx, push_tx, state_tx)
+                .await?
+                .run(),
+        );
+        Ok((
+            TradeContext {
+                command_tx,
+                state_rx,
+                events_tx,
+                dropped,
+                http_cli,
+            },
+            push_rx,
+        ))
+    }
+
+    /// Gracefully close the trade context
+    ///
+    /// Unsubscribes all topics, waits for the replies of the pending requests,
+    /// sends a websocket close frame, and returns once the background task
+    /// has exited. The last event sent to the push event receiver is
+    /// [`ConnectionState::Closed`], and the requests sent after this fail with
+    /// [`WsClientError::ClientClosed`].
+    ///
+    /// Calling this on a closed context returns immediately.
+    pub async fn close(&self) {
+        let _ = self.command_tx.send(Command::Close).await;
+
+        // the state sender is dropped when the background task exits
+        let mut state_rx = self.state_rx.clone();
+        while state_rx.changed().await.is_ok() {}
+    }
+
+    /// Returns the current connection state
+    ///
+    /// Every change of the state is also sent to the push event receiver as
+    /// [`PushEvent::ConnectionState`].
+    pub fn state(&self) -> ConnectionState {
+        self.state_rx.borrow().clone()
+    }
+
+    /// Returns the number of push events dropped because of the
+    /// [`OverflowPolicy`](crate::OverflowPolicy), see [`Config::push_capacity`]
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Subscribe to the push events with filters
+    ///
+    /// Each call returns an independent [`EventReceiver`] that receives the
+    /// events sent after it was created, in addition to the push event
+    /// receiver returned by [`TradeContext::try_new`]. A subscriber that falls
+    /// behind does not block the others, see [`LagPolicy`](crate::trade::LagPolicy)
+    /// and [`Config::events_capacity`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     trade::{PushEvent, SubscribeEventsOptions, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let mut receiver =
+    ///     ctx.subscribe_events(SubscribeEventsOptions::new().account_nos(["ACCOUNT1"]));
+    /// while let Some(event) = receiver.recv().await {
+    ///     println!("{:?}", event);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub fn subscribe_events(&self, opts: SubscribeEventsOptions) -> EventReceiver {
+        EventReceiver::new(&self.events_tx, opts)
     }
 
     /// Subscribe
@@ -41,6 +147,7 @@ impl TradeContext {
                 topics: topics.into_iter().collect(),
                 reply_tx,
             })
+            .await
             .map_err(|_| WsClientError::ClientClosed)?;
         reply_rx.await.map_err(|_| WsClientError::ClientClosed)?
     }
@@ -58,7 +165,1190 @@ impl TradeContext {
                 topics: topics.into_iter().collect(),
                 reply_tx,
             })
+            .await
             .map_err(|_| WsClientError::ClientClosed)?;
         reply_rx.await.map_err(|_| WsClientError::ClientClosed)?
     }
+
+    /// Submit order
+    ///
+    /// The options are checked when they are built, see
+    /// [`SubmitOrderOptionsBuilder::build`](crate::trade::SubmitOrderOptionsBuilder::build).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     decimal,
+    ///     trade::{OrderSide, OrderType, SubmitOrderOptions, TimeInForceType, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = SubmitOrderOptions::builder(
+    ///     "L6VQEU00121996",
+    ///     "700.HK".parse()?,
+    ///     OrderType::LO,
+    ///     OrderSide::Buy,
+    ///     200,
+    ///     TimeInForceType::Day,
+    /// )
+    /// .submitted_price(decimal!(50i32))
+    /// .build()?;
+    /// let resp = ctx.submit_order(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn submit_order(&self, options: SubmitOrderOptions) -> Result<SubmitOrderResponse> {
+        Ok(self
+            .http_cli
+            .request(Method::POST, "/v1/whaleapi/trade/order")
+            .body(Json(options))
+            .response::<Json<SubmitOrderResponse>>()
+            .send()
+            .await?
+            .0)
+    }
+
+    /// Submit a batch of orders
+    ///
+    /// At most five requests are in flight at a time, and a `429 Too Many
+    /// Requests` response from any of them makes the others back off as well.
+    /// Returns one result per order, in the same order as `orders`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     decimal,
+    ///     trade::{OrderSide, OrderType, SubmitOrderOptions, TimeInForceType, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let orders = ["700.HK", "9988.HK"]
+    ///     .into_iter()
+    ///     .map(|symbol| {
+    ///         SubmitOrderOptions::builder(
+    ///             "L6VQEU00121996",
+    ///             symbol.parse()?,
+    ///             OrderType::LO,
+    ///             OrderSide::Buy,
+    ///             200,
+    ///             TimeInForceType::Day,
+    ///         )
+    ///         .submitted_price(decimal!(50i32))
+    ///         .build()
+    ///     })
+    ///     .collect::<Result<_, longportwhale::Error>>()?;
+    /// for resp in ctx.submit_orders(orders).await {
+    ///     println!("{:?}", resp);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn submit_orders(
+        &self,
+        orders: Vec<SubmitOrderOptions>,
+    ) -> Vec<Result<SubmitOrderResponse>> {
+        stream::iter(orders)
+            .map(|options| self.submit_order(options))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Cancel order
+    ///
+    /// Errors reported by the server are returned as
+    /// [`Error::HttpClient`](crate::Error::HttpClient) with
+    /// [`HttpClientError::OpenApi`](crate::httpclient::HttpClientError::OpenApi).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// ctx.cancel_order("L6VQEU00121996", "709043056541253632").await?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn cancel_order(
+        &self,
+        account_no: impl Into<String>,
+        order_id: impl Into<String>,
+    ) -> Result<()> {
+        #[derive(Debug, Serialize)]
+        struct Request {
+            account_no: String,
+            order_id: String,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::DELETE, "/v1/whaleapi/trade/order")
+            .query_params(Request {
+                account_no: account_no.into(),
+                order_id: order_id.into(),
+            })
+            .send()
+            .await?)
+    }
+
+    /// Cancel a batch of orders
+    ///
+    /// Takes `(account_no, order_id)` pairs. At most five requests are in
+    /// flight at a time, and a `429 Too Many Requests` response from any of
+    /// them makes the others back off as well. Returns one result per order,
+    /// in the same order as `orders`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let orders = [
+    ///     ("L6VQEU00121996", "709043056541253632"),
+    ///     ("L6VQEU00121996", "709043056541253633"),
+    /// ];
+    /// for resp in ctx.cancel_orders(orders).await {
+    ///     println!("{:?}", resp);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn cancel_orders<I, A, O>(&self, orders: I) -> Vec<Result<()>>
+    where
+        I: IntoIterator<Item = (A, O)>,
+        A: Into<String>,
+        O: Into<String>,
+    {
+        stream::iter(orders)
+            .map(|(account_no, order_id)| self.cancel_order(account_no, order_id))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Replace order
+    ///
+    /// Errors reported by the server are returned as
+    /// [`Error::HttpClient`](crate::Error::HttpClient) with
+    /// [`HttpClientError::OpenApi`](crate::httpclient::HttpClientError::OpenApi).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     decimal,
+    ///     trade::{ReplaceOrderOptions, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = ReplaceOrderOptions::new("L6VQEU00121996", "709043056541253632", 100)
+    ///     .price(decimal!(300i32));
+    /// ctx.replace_order(opts).await?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn replace_order(&self, options: ReplaceOrderOptions) -> Result<()> {
+        Ok(self
+            .http_cli
+            .request(Method::PUT, "/v1/whaleapi/trade/order")
+            .body(Json(options))
+            .send()
+            .await?)
+    }
+
+    /// Get today orders
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     trade::{GetTodayOrdersOptions, OrderStatus, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = GetTodayOrdersOptions::new("L6VQEU00121996")
+    ///     .symbol("700.HK".parse()?)
+    ///     .status([OrderStatus::Filled, OrderStatus::New]);
+    /// let resp = ctx.today_orders(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn today_orders(&self, options: GetTodayOrdersOptions) -> Result<Vec<Order>> {
+        #[derive(Deserialize)]
+        struct Response {
+            orders: Vec<Order>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/trade/order/today")
+            .query_params(options)
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .orders)
+    }
+
+    /// Get history orders
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     trade::{GetHistoryOrdersOptions, OrderSide, TradeContext},
+    ///     Config,
+    /// };
+    /// use time::macros::datetime;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = GetHistoryOrdersOptions::new("L6VQEU00121996")
+    ///     .side(OrderSide::Buy)
+    ///     .start_at(datetime!(2022-05-09 0:00 UTC))
+    ///     .end_at(datetime!(2022-05-12 0:00 UTC));
+    /// let resp = ctx.history_orders(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn history_orders(&self, options: GetHistoryOrdersOptions) -> Result<Vec<Order>> {
+        #[derive(Deserialize)]
+        struct Response {
+            orders: Vec<Order>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/trade/order/history")
+            .query_params(options)
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .orders)
+    }
+
+    /// Get order detail
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let resp = ctx
+    ///     .order_detail("L6VQEU00121996", "701276261045858304")
+    ///     .await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn order_detail(
+        &self,
+        account_no: impl Into<String>,
+        order_id: impl Into<String>,
+    ) -> Result<Order> {
+        #[derive(Debug, Serialize)]
+        struct Request {
+            account_no: String,
+            order_id: String,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/trade/order")
+            .query_params(Request {
+                account_no: account_no.into(),
+                order_id: order_id.into(),
+            })
+            .response::<Json<Order>>()
+            .send()
+            .await?
+            .0)
+    }
+
+    /// Get today executions
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     trade::{GetTodayExecutionsOptions, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = GetTodayExecutionsOptions::new("L6VQEU00121996").symbol("700.HK".parse()?);
+    /// let resp = ctx.today_executions(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn today_executions(
+        &self,
+        options: GetTodayExecutionsOptions,
+    ) -> Result<Vec<Execution>> {
+        #[derive(Deserialize)]
+        struct Response {
+            trades: Vec<Execution>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/trade/execution/today")
+            .query_params(options)
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .trades)
+    }
+
+    /// Get history executions
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     trade::{GetHistoryExecutionsOptions, TradeContext},
+    ///     Config,
+    /// };
+    /// use time::macros::datetime;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = GetHistoryExecutionsOptions::new("L6VQEU00121996")
+    ///     .symbol("700.HK".parse()?)
+    ///     .start_at(datetime!(2022-05-09 0:00 UTC))
+    ///     .end_at(datetime!(2022-05-12 0:00 UTC));
+    /// let resp = ctx.history_executions(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn history_executions(
+        &self,
+        options: GetHistoryExecutionsOptions,
+    ) -> Result<Vec<Execution>> {
+        #[derive(Deserialize)]
+        struct Response {
+            trades: Vec<Execution>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/trade/execution/history")
+            .query_params(options)
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .trades)
+    }
+
+    /// Get account balance
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let resp = ctx.account_balance("L6VQEU00121996", "HKD").await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn account_balance(
+        &self,
+        account_no: impl Into<String>,
+        currency: impl Into<String>,
+    ) -> Result<AccountBalance> {
+        #[derive(Debug, Serialize)]
+        struct Request {
+            account_no: String,
+            currency: String,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::POST, "/v1/whaleapi/asset/detail_info")
+            .body(Json(Request {
+                account_no: account_no.into(),
+                currency: currency.into(),
+            }))
+            .response::<Json<AccountBalance>>()
+            .send()
+            .await?
+            .0)
+    }
+
+    /// Get stock positions
+    ///
+    /// If `symbols` is empty, the positions of all stocks are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let resp = ctx
+    ///     .stock_positions("L6VQEU00121996", ["700.HK".parse()?, "AAPL.US".parse()?])
+    ///     .await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn stock_positions(
+        &self,
+        account_no: impl Into<String>,
+        symbols: impl IntoIterator<Item = Symbol>,
+    ) -> Result<Vec<StockPositionChannel>> {
+        #[derive(Debug, Serialize)]
+        struct Request {
+            account_no: String,
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            symbol: Vec<Symbol>,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            channels: Vec<StockPositionChannel>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/asset/stock")
+            .query_params(Request {
+                account_no: account_no.into(),
+                symbol: symbols.into_iter().collect(),
+            })
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .channels)
+    }
+
+    /// Get fund positions
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let resp = ctx.fund_positions("L6VQEU00121996").await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn fund_positions(
+        &self,
+        account_no: impl Into<String>,
+    ) -> Result<Vec<FundPositionChannel>> {
+        #[derive(Debug, Serialize)]
+        struct Request {
+            account_no: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(rename = "list")]
+            channels: Vec<FundPositionChannel>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/asset/fund")
+            .query_params(Request {
+                account_no: account_no.into(),
+            })
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .channels)
+    }
+
+    /// Get cash flow
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     trade::{CashFlowOptions, TradeContext},
+    ///     Config,
+    /// };
+    /// use time::OffsetDateTime;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = CashFlowOptions::new(
+    ///     "L6VQEU00121996",
+    ///     OffsetDateTime::UNIX_EPOCH,
+    ///     OffsetDateTime::now_utc(),
+    /// )
+    /// .page(1)
+    /// .size(50);
+    /// let resp = ctx.cash_flow(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn cash_flow(&self, options: CashFlowOptions) -> Result<Vec<CashFlow>> {
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(default)]
+            list: Vec<CashFlow>,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/asset/cashflow")
+            .query_params(options)
+            .response::<Json<Response>>()
+            .send()
+            .await?
+            .0
+            .list)
+    }
+
+    /// Estimating the maximum purchase quantity for Hong Kong and US stocks,
+    /// warrants, and options
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{
+    ///     decimal,
+    ///     trade::{EstimateMaxPurchaseQuantityOptions, OrderSide, OrderType, TradeContext},
+    ///     Config,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let opts = EstimateMaxPurchaseQuantityOptions::new(
+    ///     "L6VQEU00121996",
+    ///     "700.HK".parse()?,
+    ///     OrderType::LO,
+    ///     OrderSide::Buy,
+    /// )
+    /// .price(decimal!(50i32));
+    /// let resp = ctx.estimate_max_purchase_quantity(opts).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn estimate_max_purchase_quantity(
+        &self,
+        options: EstimateMaxPurchaseQuantityOptions,
+    ) -> Result<EstimateMaxPurchaseQuantityResponse> {
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/trade/estimate/buy_limit")
+            .query_params(options)
+            .response::<Json<EstimateMaxPurchaseQuantityResponse>>()
+            .send()
+            .await?
+            .0)
+    }
+
+    /// Get margin ratio
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longportwhale::{trade::TradeContext, Config};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// let resp = ctx.margin_ratio("700.HK".parse()?).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn margin_ratio(&self, symbol: Symbol) -> Result<MarginRatio> {
+        #[derive(Debug, Serialize)]
+        struct Request {
+            symbol: Symbol,
+        }
+
+        Ok(self
+            .http_cli
+            .request(Method::GET, "/v1/whaleapi/risk/margin-ratio")
+            .query_params(Request { symbol })
+            .response::<Json<MarginRatio>>()
+            .send()
+            .await?
+            .0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use longportwhale_httpcli::HttpClientError;
+    use longportwhale_wscli::mock::{HttpResponse, MockServer};
+
+    use super::*;
+    use crate::{
+        trade::{push_types, OrderSide, OrderStatus, OrderType, TimeInForceType},
+        Error, OverflowPolicy, ReconnectPolicy, SignatureKey,
+    };
+
+    fn order_changed() -> String {
+        push_types::tests::order_changed_json(OrderStatus::New, 0).to_string()
+    }
+
+    fn config(server: &MockServer) -> Config {
+        Config::new("app-key", "app-secret", "access-token")
+            .http_url(server.http_url())
+            .trade_ws_url(server.ws_url())
+            .reconnect_policy(
+                ReconnectPolicy::default()
+                    .initial_delay(Duration::from_millis(10))
+                    .jitter(0.0),
+            )
+    }
+
+    async fn connect(config: Config) -> (TradeContext, PushEventReceiver) {
+        TradeContext::try_new(Arc::new(config)).await.unwrap()
+    }
+
+    /// Waits until the context is authenticated again after a disconnect
+    async fn wait_reconnected(receiver: &mut PushEventReceiver) {
+        let mut disconnected = false;
+        while let Some(event) = receiver.recv().await {
+            match event {
+                PushEvent::ConnectionState(ConnectionState::Disconnected { .. }) => {
+                    disconnected = true
+                }
+                PushEvent::ConnectionState(ConnectionState::Authenticated) if disconnected => {
+                    return
+                }
+                _ => {}
+            }
+        }
+        panic!("trade context closed");
+    }
+
+    async fn wait_subscribed(server: &MockServer) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while server.subscriptions().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reconnect_and_resubscribe() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, mut receiver) = connect(config(&server)).await;
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+
+        server.disconnect();
+        wait_reconnected(&mut receiver).await;
+        wait_subscribed(&server).await;
+        assert_eq!(server.requests(2), 1);
+        assert_eq!(server.requests(3), 1);
+        assert_eq!(server.requests(16), 2);
+
+        server.push_order_changed(&order_changed());
+        loop {
+            if let Some(PushEvent::OrderChanged(order_changed)) = receiver.recv().await {
+                assert_eq!(order_changed.order_id, "1");
+                break;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn reauthenticate_after_session_expired() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, mut receiver) = connect(config(&server)).await;
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+
+        server.expire_sessions();
+        server.disconnect();
+        wait_reconnected(&mut receiver).await;
+        wait_subscribed(&server).await;
+        assert_eq!(server.requests(3), 1);
+        assert_eq!(server.requests(2), 2);
+    }
+
+    #[tokio::test]
+    async fn subscribe_error_response() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, _receiver) = connect(config(&server)).await;
+
+        server.fail_next(16, 3, 401003, "permission denied");
+        let err = ctx.subscribe([TopicType::Private]).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::WsClient(WsClientError::ResponseError {
+                status: 3,
+                detail: Some(detail),
+            }) if detail.code == 401003
+        ));
+
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+        assert!(server.subscriptions().contains("private"));
+    }
+
+    #[tokio::test]
+    async fn reject_unsigned_pushes() {
+        let server = MockServer::start().await.unwrap();
+        server.set_signature_key(Some("app-secret"));
+        let (ctx, mut receiver) = connect(
+            config(&server)
+                .packet_signature(SignatureKey::AppSecret)
+                .reject_unsigned_pushes(true),
+        )
+        .await;
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+        assert_eq!(server.signed_requests(), 2);
+
+        server.push_order_changed(&order_changed());
+        loop {
+            if let Some(PushEvent::OrderChanged(_)) = receiver.recv().await {
+                break;
+            }
+        }
+
+        server.set_signature_key(None::<Vec<u8>>);
+        server.push_order_changed(&order_changed());
+        loop {
+            match receiver.recv().await {
+                Some(PushEvent::OrderChanged(_)) => panic!("unsigned push received"),
+                Some(PushEvent::ConnectionState(ConnectionState::Disconnected { .. })) => break,
+                _ => {}
+            }
+        }
+    }
+
+    fn order(
+        order_id: &str,
+        status: &str,
+        executed_quantity: i64,
+        updated_at: i64,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "order_id": order_id,
+            "status": status,
+            "stock_name": "Tencent",
+            "quantity": "200",
+            "executed_quantity": executed_quantity.to_string(),
+            "price": "300",
+            "executed_price": "300",
+            "submitted_at": "1700000000",
+            "side": "Buy",
+            "symbol": "700.HK",
+            "order_type": "LO",
+            "last_done": "300",
+            "trigger_price": "",
+            "msg": "",
+            "tag": "Normal",
+            "time_in_force": "Day",
+            "expire_date": "",
+            "updated_at": updated_at.to_string(),
+            "trigger_at": "0",
+            "trailing_amount": "",
+            "trailing_percent": "",
+            "limit_offset": "",
+            "trigger_status": "NOT_USED",
+            "currency": "HKD",
+            "outside_rth": "",
+            "account_no": "A",
+            "remark": ""
+        })
+    }
+
+    #[tokio::test]
+    async fn recover_orders_after_reconnect() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, mut receiver) = connect(config(&server).recover_orders(["A"])).await;
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+
+        server.push_order_changed(&order_changed());
+        loop {
+            if let Some(PushEvent::OrderChanged(_)) = receiver.recv().await {
+                break;
+            }
+        }
+
+        // the order is filled during the outage, the other order has not
+        // changed since long before it
+        server.set_http_response(
+            "/v1/whaleapi/trade/order/today",
+            serde_json::json!({
+                "orders": [
+                    order("2", "FilledStatus", 200, 1600000000),
+                    order("1", "FilledStatus", 200, 1700000100),
+                ]
+            })
+            .to_string(),
+        );
+        server.disconnect();
+        wait_reconnected(&mut receiver).await;
+
+        let Some(PushEvent::OrderChanged(order_changed)) = receiver.recv().await else {
+            panic!("order changed event expected");
+        };
+        assert_eq!(order_changed.order_id, "1");
+        assert_eq!(order_changed.status, OrderStatus::Filled);
+        assert_eq!(order_changed.executed_quantity, 200);
+        assert!(order_changed.recovered);
+        assert!(matches!(
+            receiver.recv().await,
+            Some(PushEvent::Execution(execution)) if execution.quantity == 200 && execution.recovered
+        ));
+    }
+
+    #[tokio::test]
+    async fn give_up_reconnecting() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, mut receiver) = connect(
+            config(&server).reconnect_policy(
+                ReconnectPolicy::default()
+                    .initial_delay(Duration::from_millis(200))
+                    .jitter(0.0)
+                    .max_attempts(2),
+            ),
+        )
+        .await;
+
+        server.refuse_connections(true);
+        server.disconnect();
+        loop {
+            if let Some(PushEvent::ConnectionState(ConnectionState::Disconnected { .. })) =
+                receiver.recv().await
+            {
+                break;
+            }
+        }
+
+        // queued until reconnected, then failed when giving up
+        let err = ctx.subscribe([TopicType::Private]).await.unwrap_err();
+        assert!(matches!(err, Error::ReconnectGaveUp { attempts: 2 }));
+
+        let mut attempts = 0;
+        loop {
+            match receiver.recv().await {
+                Some(PushEvent::ConnectionState(ConnectionState::Reconnecting { attempt })) => {
+                    attempts = attempt
+                }
+                Some(PushEvent::ConnectionState(ConnectionState::Closed { error })) => {
+                    assert!(matches!(
+                        error.as_deref(),
+                        Some(Error::ReconnectGaveUp { attempts: 2 })
+                    ));
+                    break;
+                }
+                Some(_) => {}
+                None => panic!("closed event expected"),
+            }
+        }
+        assert_eq!(attempts, 2);
+        assert!(receiver.recv().await.is_none());
+        assert!(matches!(ctx.state(), ConnectionState::Closed { .. }));
+        assert!(matches!(
+            ctx.subscribe([TopicType::Private]).await,
+            Err(Error::WsClient(WsClientError::ClientClosed))
+        ));
+    }
+
+    #[tokio::test]
+    async fn close() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, mut receiver) = connect(config(&server)).await;
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+
+        ctx.close().await;
+        assert_eq!(server.requests(17), 1);
+        assert!(matches!(
+            ctx.state(),
+            ConnectionState::Closed { error: None }
+        ));
+
+        let mut last_event = None;
+        while let Some(event) = receiver.recv().await {
+            last_event = Some(event);
+        }
+        assert!(matches!(
+            last_event,
+            Some(PushEvent::ConnectionState(ConnectionState::Closed {
+                error: None
+            }))
+        ));
+
+        assert!(matches!(
+            ctx.subscribe([TopicType::Private]).await,
+            Err(Error::WsClient(WsClientError::ClientClosed))
+        ));
+        tokio::time::timeout(Duration::from_secs(1), ctx.close())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn connection_state_does_not_block() {
+        let server = MockServer::start().await.unwrap();
+        let config = config(&server).push_capacity(1, OverflowPolicy::Block);
+        let (ctx, mut receiver) = tokio::time::timeout(Duration::from_secs(5), connect(config))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(PushEvent::ConnectionState(ConnectionState::Connecting))
+        ));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(PushEvent::ConnectionState(ConnectionState::Connected))
+        ));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(PushEvent::ConnectionState(ConnectionState::Authenticated))
+        ));
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fail_fast_keeps_the_context_open() {
+        let server = MockServer::start().await.unwrap();
+        let config = config(&server).push_capacity(1, OverflowPolicy::FailFast);
+        let (ctx, mut receiver) = connect(config).await;
+        ctx.subscribe([TopicType::Private]).await.unwrap();
+
+        server.push_order_changed(&order_changed());
+        server.push_order_changed(&order_changed());
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while ctx.dropped_events() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert!(matches!(ctx.state(), ConnectionState::Authenticated));
+        ctx.unsubscribe([TopicType::Private]).await.unwrap();
+        ctx.close().await;
+
+        let mut order_changed_events = 0;
+        while let Some(event) = receiver.recv().await {
+            if let PushEvent::OrderChanged(_) = event {
+                order_changed_events += 1;
+            }
+        }
+        assert_eq!(order_changed_events, 1);
+        assert_eq!(ctx.dropped_events(), 1);
+    }
+
+    const ORDER_PATH: &str = "/v1/whaleapi/trade/order";
+
+    #[tokio::test]
+    async fn submit_orders_in_order() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, _receiver) = connect(config(&server)).await;
+        server.set_http_handler(ORDER_PATH, |req| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            match body["submitted_quantity"].as_str().unwrap() {
+                "300" => HttpResponse::Error {
+                    code: 602001,
+                    message: "invalid quantity".to_string(),
+                },
+                quantity => HttpResponse::Data(format!(r#"{{"order_id":"{quantity}"}}"#)),
+            }
+        });
+
+        let orders = (1..=12)
+            .map(|n| {
+                SubmitOrderOptions::builder(
+                    "A",
+                    "700.HK".parse().unwrap(),
+                    OrderType::MO,
+                    OrderSide::Buy,
+                    n * 100,
+                    TimeInForceType::Day,
+                )
+                .build()
+                .unwrap()
+            })
+            .collect();
+        let results = ctx.submit_orders(orders).await;
+
+        assert_eq!(results.len(), 12);
+        for (n, res) in (1..=12).zip(results) {
+            match res {
+                Err(Error::HttpClient(HttpClientError::OpenApi { code: 602001, .. })) => {
+                    assert_eq!(n, 3)
+                }
+                Ok(resp) => assert_eq!(resp.order_id, (n * 100).to_string()),
+                Err(err) => panic!("unexpected error: {err}"),
+            }
+        }
+        assert_eq!(server.http_requests(ORDER_PATH), 12);
+    }
+
+    #[tokio::test]
+    async fn cancel_orders_in_order() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, _receiver) = connect(config(&server)).await;
+        server.set_http_handler(ORDER_PATH, |req| {
+            assert_eq!(req.method, "DELETE");
+            if req.query.contains("order_id=2") {
+                HttpResponse::Error {
+                    code: 603001,
+                    message: "order not found".to_string(),
+                }
+            } else {
+                HttpResponse::Data("{}".to_string())
+            }
+        });
+
+        let results = ctx
+            .cancel_orders([("A", "1"), ("A", "2"), ("A", "3")])
+            .await;
+        assert!(matches!(
+            results.as_slice(),
+            [
+                Ok(()),
+                Err(Error::HttpClient(HttpClientError::OpenApi {
+                    code: 603001,
+                    ..
+                })),
+                Ok(())
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn too_many_requests_delays_other_requests() {
+        let server = MockServer::start().await.unwrap();
+        let (ctx, _receiver) = connect(config(&server)).await;
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        server.set_http_handler(ORDER_PATH, {
+            let received = received.clone();
+            move |_| {
+                let mut received = received.lock().unwrap();
+                received.push(std::time::Instant::now());
+                if received.len() == 1 {
+                    HttpResponse::Status(429)
+                } else {
+                    HttpResponse::Data("{}".to_string())
+                }
+            }
+        });
+
+        let first = tokio::spawn({
+            let ctx = ctx.clone();
+            async move { ctx.cancel_order("A", "1").await }
+        });
+        while server.http_requests(ORDER_PATH) == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        // lets the client receive the 429 response
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        ctx.cancel_order("A", "2").await.unwrap();
+        first.await.unwrap().unwrap();
+
+        // both the retry and the other request waited for the backoff window
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        for at in &received[1..] {
+            assert!(*at - received[0] >= Duration::from_millis(100));
+        }
+    }
 }
@@ -1,18 +1,32 @@
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
 
+use bytes::Bytes;
 use longport_proto::trade::{Sub, SubResponse, Unsub, UnsubResponse};
-use longportwhale_httpcli::HttpClient;
+use longportwhale_httpcli::{HttpClient, Json, Method};
 use longportwhale_wscli::{
     CodecType, Platform, ProtocolVersion, WsClient, WsClientError, WsEvent, WsSession,
 };
-use tokio::sync::{mpsc, oneshot};
+use serde::Deserialize;
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot, watch};
 
 use crate::{
-    trade::{cmd_code, PushEvent, TopicType},
-    Config, Result,
+    trade::{
+        cmd_code, events::PushSender, executions::ExecutionTracker, journal::Journal,
+        ConnectionState, GetTodayOrdersOptions, Order, OrderStatus, PushEvent, PushOrderChanged,
+        TopicType,
+    },
+    Config, Error, ReconnectPolicy, Result, SignatureKey,
 };
 
-const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+/// Orders updated less than this long before the connection was lost are
+/// recovered even if no event has been received for them, to tolerate clock
+/// skew between the client and the server
+const RECOVER_ORDERS_LOOKBACK: time::Duration = time::Duration::minutes(1);
 
 pub(crate) enum Command {
     Subscribe {
@@ -23,12 +37,45 @@ pub(crate) enum Command {
         topics: Vec<TopicType>,
         reply_tx: oneshot::Sender<Result<()>>,
     },
+    Close,
+}
+
+impl Command {
+    fn reply_error(self, err: Error) {
+        match self {
+            Command::Subscribe { reply_tx, .. } | Command::Unsubscribe { reply_tx, .. } => {
+                let _ = reply_tx.send(Err(err));
+            }
+            Command::Close => {}
+        }
+    }
+}
+
+/// The last known state of an order, used to recover missed order updates
+struct OrderSnapshot {
+    account_no: String,
+    status: OrderStatus,
+    executed_quantity: i64,
+    updated_at: OffsetDateTime,
+}
+
+impl OrderSnapshot {
+    fn from_event(event: &PushOrderChanged) -> Self {
+        Self {
+            account_no: event.account_no.clone(),
+            status: event.status,
+            executed_quantity: event.executed_quantity,
+            updated_at: event.updated_at,
+        }
+    }
 }
 
 pub(crate) struct Core {
     config: Arc<Config>,
-    command_rx: mpsc::UnboundedReceiver<Command>,
-    push_tx: mpsc::UnboundedSender<PushEvent>,
+    command_rx: mpsc::Receiver<Command>,
+    pending_commands: VecDeque<Command>,
+    push_tx: PushSender,
+    state_tx: watch::Sender<ConnectionState>,
     event_tx: mpsc::UnboundedSender<WsEvent>,
     event_rx: mpsc::UnboundedReceiver<WsEvent>,
     http_cli: HttpClient,
@@ -36,24 +83,34 @@ pub(crate) struct Core {
     session: Option<WsSession>,
     close: bool,
     subscriptions: HashSet<String>,
+    orders: HashMap<String, OrderSnapshot>,
+    executions: ExecutionTracker,
+    journal: Option<Journal>,
+    disconnected_at: Option<OffsetDateTime>,
 }
 
 impl Core {
+    /// `http_cli` is shared with the trade context, so that the OTP and the
+    /// order recovery requests back off with the other requests
     pub(crate) async fn try_new(
         config: Arc<Config>,
-        command_rx: mpsc::UnboundedReceiver<Command>,
-        push_tx: mpsc::UnboundedSender<PushEvent>,
+        http_cli: HttpClient,
+        command_rx: mpsc::Receiver<Command>,
+        push_tx: PushSender,
+        state_tx: watch::Sender<ConnectionState>,
     ) -> Result<Self> {
-        let http_cli = config.create_http_client();
+        let journal = config.journal.as_ref().map(Journal::open).transpose()?;
         let otp = http_cli.get_otp().await?;
 
+        set_state(&state_tx, &push_tx, ConnectionState::Connecting).await;
+
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
         tracing::debug!(
             url = config.trade_ws_url.as_str(),
             "connecting to trade server",
         );
-        let ws_cli = WsClient::open(
+        let mut ws_cli = WsClient::open(
             config
                 .create_trade_ws_request()
                 .map_err(WsClientError::from)?,
@@ -64,15 +121,24 @@ impl Core {
             vec![],
         )
         .await?;
+        ws_cli.set_gzip_threshold(config.request_gzip_threshold);
+        ws_cli.set_signature(config.create_packet_signature(None))?;
 
         tracing::debug!(url = config.trade_ws_url.as_str(), "trade server connected");
+        set_state(&state_tx, &push_tx, ConnectionState::Connected).await;
 
         let session = ws_cli.request_auth(otp).await?;
+        if config.signature_key == Some(SignatureKey::Session) {
+            ws_cli.set_signature(config.create_packet_signature(Some(&session)))?;
+        }
+        set_state(&state_tx, &push_tx, ConnectionState::Authenticated).await;
 
         Ok(Self {
             config,
             command_rx,
+            pending_commands: VecDeque::new(),
             push_tx,
+            state_tx,
             event_tx,
             event_rx,
             http_cli,
@@ -80,19 +146,47 @@ impl Core {
             session: Some(session),
             close: false,
             subscriptions: HashSet::new(),
+            orders: HashMap::new(),
+            executions: ExecutionTracker::default(),
+            journal,
+            disconnected_at: None,
         })
     }
 
     pub(crate) async fn run(mut self) {
         while !self.close {
             match self.main_loop().await {
-                Ok(()) => return,
-                Err(err) => tracing::error!(error = %err, "trade disconnected"),
+                Ok(()) => break,
+                Err(err) => {
+                    tracing::error!(error = %err, "trade disconnected");
+                    self.disconnected_at = Some(OffsetDateTime::now_utc());
+                    let reason = match err {
+                        Error::WsClient(WsClientError::ConnectionClosed { reason }) => reason,
+                        _ => None,
+                    };
+                    self.set_state(ConnectionState::Disconnected { reason })
+                        .await;
+                }
             }
 
+            let policy = self.config.reconnect_policy.clone();
+            let mut attempt = 0;
+
             loop {
+                if policy.exhausted(attempt) {
+                    self.give_up(&policy, attempt).await;
+                    return;
+                }
+
                 // reconnect
-                tokio::time::sleep(RECONNECT_DELAY).await;
+                if !self.wait_reconnect(policy.delay(attempt)).await {
+                    self.close = true;
+                    break;
+                }
+
+                attempt += 1;
+                self.set_state(ConnectionState::Reconnecting { attempt })
+                    .await;
 
                 tracing::debug!(
                     url = self.config.trade_ws_url.as_str(),
@@ -109,17 +203,29 @@ impl Core {
                 )
                 .await
                 {
-                    Ok(ws_cli) => self.ws_cli = ws_cli,
+                    Ok(mut ws_cli) => {
+                        ws_cli.set_gzip_threshold(self.config.request_gzip_threshold);
+                        self.ws_cli = ws_cli;
+                    }
                     Err(err) => {
                         tracing::error!(error = %err, "failed to connect trade server");
                         continue;
                     }
                 }
 
+                if let Err(err) = self
+                    .ws_cli
+                    .set_signature(self.config.create_packet_signature(None))
+                {
+                    tracing::error!(error = %err, "failed to connect trade server");
+                    continue;
+                }
+
                 tracing::debug!(
                     url = self.config.trade_ws_url.as_str(),
                     "trade server connected"
                 );
+                self.set_state(ConnectionState::Connected).await;
 
                 // request new session
                 match &self.session {
@@ -152,6 +258,18 @@ impl Core {
                     }
                 }
 
+                if self.config.signature_key == Some(SignatureKey::Session) {
+                    if let Err(err) = self
+                        .ws_cli
+                        .set_signature(self.config.create_packet_signature(self.session.as_ref()))
+                    {
+                        tracing::error!(error = %err, "failed to connect trade server");
+                        continue;
+                    }
+                }
+
+                self.set_state(ConnectionState::Authenticated).await;
+
                 // handle reconnect
                 match self.resubscribe().await {
                     Ok(()) => break,
@@ -161,22 +279,105 @@ impl Core {
                     }
                 }
             }
+
+            if self.close {
+                break;
+            }
+
+            if !self.config.recover_orders.is_empty() {
+                if let Err(err) = self.recover_orders().await {
+                    tracing::error!(error = %err, "failed to recover orders");
+                }
+            }
+        }
+
+        self.shutdown(None, || WsClientError::ClientClosed.into())
+            .await;
+        if let Some(journal) = self.journal.take() {
+            journal.close().await;
+        }
+    }
+
+    /// Waits for the reconnect delay, the commands received meanwhile are
+    /// queued until reconnected
+    ///
+    /// Returns `false` if the context is closed.
+    async fn wait_reconnect(&mut self, delay: Duration) -> bool {
+        let sleep = tokio::time::sleep(delay);
+        tokio::pin!(sleep);
+
+        loop {
+            tokio::select! {
+                _ = &mut sleep => return true,
+                item = self.command_rx.recv() => {
+                    match item {
+                        Some(Command::Close) | None => return false,
+                        Some(command) => self.pending_commands.push_back(command),
+                    }
+                }
+            }
+        }
+    }
+
+    async fn give_up(&mut self, policy: &ReconnectPolicy, attempts: u32) {
+        tracing::error!(attempts, "gave up reconnecting to trade server");
+        policy.give_up(&Error::ReconnectGaveUp { attempts });
+        self.shutdown(Some(Error::ReconnectGaveUp { attempts }), || {
+            Error::ReconnectGaveUp { attempts }
+        })
+        .await;
+    }
+
+    /// Fails the pending commands with the error returned by `reply_error`, and
+    /// sends the [`ConnectionState::Closed`] event
+    ///
+    /// The commands sent after this fail with `WsClientError::ClientClosed`.
+    async fn shutdown(&mut self, error: Option<Error>, reply_error: impl Fn() -> Error) {
+        self.command_rx.close();
+        let mut commands = std::mem::take(&mut self.pending_commands);
+        while let Ok(command) = self.command_rx.try_recv() {
+            commands.push_back(command);
+        }
+        for command in commands {
+            command.reply_error(reply_error());
         }
+
+        self.set_state(ConnectionState::Closed {
+            error: error.map(Arc::new),
+        })
+        .await;
     }
 
     #[tracing::instrument(level = "debug", skip(self))]
     async fn main_loop(&mut self) -> Result<()> {
+        while let Some(command) = self.pending_commands.pop_front() {
+            self.handle_command(command).await?;
+            if self.close {
+                return Ok(());
+            }
+        }
+
         loop {
             tokio::select! {
                 item = self.event_rx.recv() => {
                     match item {
-                        Some(event) => self.handle_ws_event(event).await?,
+                        Some(event) => {
+                            self.handle_ws_event(event).await?;
+                            if self.close {
+                                return Ok(());
+                            }
+                        }
                         None => unreachable!(),
                     }
                 }
                 item = self.command_rx.recv() => {
                     match item {
-                        Some(command) => self.handle_command(command).await?,
+                        Some(command) => {
+                            self.handle_command(command).await?;
+                            if self.close {
+                                return Ok(());
+                            }
+                        }
                         None => {
                             self.close = true;
                             return Ok(());
@@ -194,12 +395,20 @@ impl Core {
         }
     }
 
-    async fn handle_push(&mut self, command_code: u8, body: Vec<u8>) -> Result<()> {
-        match PushEvent::parse(command_code, &body) {
-            Ok(Some(event)) => {
-                let _ = self.push_tx.send(event);
+    async fn handle_push(&mut self, command_code: u8, body: Bytes) -> Result<()> {
+        if let Some(journal) = &self.journal {
+            // the journal is only for diagnosis, the push is delivered anyway
+            if let Err(err) = journal.record(command_code, body.clone()).await {
+                tracing::error!(
+                    error = %err,
+                    dropped = journal.dropped(),
+                    "failed to record push message"
+                );
             }
-            Ok(None) => {}
+        }
+
+        match PushEvent::parse(command_code, body) {
+            Ok(event) => self.push(event).await,
             Err(err) => {
                 tracing::error!(error = %err, "failed to parse push message")
             }
@@ -219,6 +428,31 @@ impl Core {
                 let _ = reply_tx.send(res);
                 Ok(())
             }
+            Command::Close => {
+                self.handle_close().await;
+                self.close = true;
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_close(&mut self) {
+        if !self.subscriptions.is_empty() {
+            let req = Unsub {
+                topics: self.subscriptions.iter().cloned().collect(),
+            };
+            match self
+                .ws_cli
+                .request::<_, UnsubResponse>(cmd_code::UNSUBSCRIBE, None, req)
+                .await
+            {
+                Ok(_) => self.subscriptions.clear(),
+                Err(err) => tracing::error!(error = %err, "failed to unsubscribe topics"),
+            }
+        }
+
+        if let Err(err) = self.ws_cli.close().await {
+            tracing::error!(error = %err, "failed to close the connection");
         }
     }
 
@@ -252,4 +486,99 @@ impl Core {
         self.subscriptions = resp.current.into_iter().collect();
         Ok(())
     }
+
+    /// Fetches today's orders and sends a recovered [`PushOrderChanged`] for
+    /// each order that has changed since the last event received for it
+    async fn recover_orders(&mut self) -> Result<()> {
+        #[derive(Deserialize)]
+        struct Response {
+            orders: Vec<Order>,
+        }
+
+        let since = self
+            .disconnected_at
+            .take()
+            .unwrap_or_else(OffsetDateTime::now_utc)
+            - RECOVER_ORDERS_LOOKBACK;
+
+        for account_no in self.config.recover_orders.clone() {
+            let orders = self
+                .http_cli
+                .request(Method::GET, "/v1/whaleapi/trade/order/today")
+                .query_params(GetTodayOrdersOptions::new(&account_no))
+                .response::<Json<Response>>()
+                .send()
+                .await?
+                .0
+                .orders;
+
+            // orders that are no longer returned belong to a previous trading day
+            let order_ids = orders
+                .iter()
+                .map(|order| order.order_id.as_str())
+                .collect::<HashSet<_>>();
+            self.orders.retain(|order_id, snapshot| {
+                snapshot.account_no != account_no || order_ids.contains(order_id.as_str())
+            });
+
+            for order in orders {
+                let updated_at = order.updated_at.unwrap_or(order.submitted_at);
+                let changed = match self.orders.get(&order.order_id) {
+                    Some(snapshot) => {
+                        snapshot.status != order.status
+                            || snapshot.executed_quantity != order.executed_quantity
+                            || snapshot.updated_at < updated_at
+                    }
+                    None => updated_at >= since,
+                };
+                if !changed {
+                    continue;
+                }
+
+                let order_changed = PushOrderChanged::recovered(order);
+                tracing::debug!(
+                    order_id = order_changed.order_id.as_str(),
+                    "recovered order changed"
+                );
+                self.push(PushEvent::OrderChanged(order_changed)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends a push event, see [`ExecutionTracker::dispatch`]
+    ///
+    /// An event dropped because of
+    /// [`OverflowPolicy::FailFast`](crate::OverflowPolicy::FailFast) is only
+    /// logged and counted, the trade context stays open.
+    async fn push(&mut self, event: PushEvent) {
+        if let PushEvent::OrderChanged(order_changed) = &event {
+            if !self.config.recover_orders.is_empty() {
+                self.orders.insert(
+                    order_changed.order_id.clone(),
+                    OrderSnapshot::from_event(order_changed),
+                );
+            }
+        }
+
+        if let Err(err) = self.executions.dispatch(&self.push_tx, event).await {
+            tracing::error!(error = %err, "failed to send push event");
+        }
+    }
+
+    #[inline]
+    async fn set_state(&self, state: ConnectionState) {
+        set_state(&self.state_tx, &self.push_tx, state).await;
+    }
+}
+
+async fn set_state(
+    state_tx: &watch::Sender<ConnectionState>,
+    push_tx: &PushSender,
+    state: ConnectionState,
+) {
+    state_tx.send_replace(state.clone());
+    // the connection state events are never dropped nor wait for the receiver
+    let _ = push_tx.send(PushEvent::ConnectionState(state)).await;
 }
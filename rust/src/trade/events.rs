@@ -0,0 +1,618 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use futures_util::{stream, Stream};
+use parking_lot::Mutex;
+use tokio::sync::{broadcast, Semaphore, TryAcquireError};
+
+use crate::{trade::PushEvent, Config, Error, OverflowPolicy, Result, Symbol};
+
+/// Sends the push events to the receiver returned by
+/// [`TradeContext::try_new`](crate::trade::TradeContext::try_new) and to the
+/// subscribers
+///
+/// The capacity only bounds the non connection state events, so that the
+/// connection state events never wait for the receiver, nor take the room of
+/// another event, nor are dropped to make room for another event.
+pub(crate) struct PushSender {
+    // the events not received yet, shared with the receiver so that
+    // `DropOldest` can drop the oldest non connection state event
+    queue: Arc<Mutex<VecDeque<PushEvent>>>,
+    // wakes up the receiver, disconnected when the receiver is dropped
+    wake_tx: flume::Sender<()>,
+    // the room left in the queue, closed when the receiver is dropped
+    permits: Option<Arc<Semaphore>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+    events_tx: broadcast::Sender<PushEvent>,
+}
+
+impl PushSender {
+    #[inline]
+    pub(crate) fn new(config: &Config) -> (Self, PushEventReceiver) {
+        Self::with_capacity(
+            config.push_capacity,
+            config.overflow_policy,
+            config.events_capacity,
+        )
+    }
+
+    pub(crate) fn with_capacity(
+        capacity: Option<usize>,
+        policy: OverflowPolicy,
+        events_capacity: usize,
+    ) -> (Self, PushEventReceiver) {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        let (wake_tx, wake_rx) = flume::bounded(1);
+        let permits = capacity.map(|capacity| Arc::new(Semaphore::new(capacity)));
+        let (events_tx, _) = broadcast::channel(events_capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let sender = Self {
+            queue: queue.clone(),
+            wake_tx,
+            permits: permits.clone(),
+            capacity: capacity.unwrap_or_default(),
+            policy,
+            dropped: dropped.clone(),
+            events_tx,
+        };
+        (
+            sender,
+            PushEventReceiver {
+                queue,
+                wake_rx,
+                permits,
+                dropped,
+            },
+        )
+    }
+
+    /// Sends an event according to the [`OverflowPolicy`]
+    ///
+    /// Returns [`Error::PushChannelFull`] if the event is dropped because of
+    /// [`OverflowPolicy::FailFast`]. The connection state events are never
+    /// dropped this way, and never wait for the receiver because of
+    /// [`OverflowPolicy::Block`].
+    pub(crate) async fn send(&self, event: PushEvent) -> Result<()> {
+        if self.events_tx.receiver_count() > 0 {
+            let _ = self.events_tx.send(event.clone());
+        }
+
+        if self.wake_tx.is_disconnected() {
+            return Ok(());
+        }
+        if let Some(permits) = &self.permits {
+            if !matches!(event, PushEvent::ConnectionState(_)) && !self.reserve(permits).await? {
+                return Ok(());
+            }
+        }
+        self.queue.lock().push_back(event);
+        // a pending wake up is enough if the channel is full
+        let _ = self.wake_tx.try_send(());
+        Ok(())
+    }
+
+    /// Makes room for a non connection state event according to the
+    /// [`OverflowPolicy`]
+    ///
+    /// Returns `false` if the receiver has been dropped.
+    async fn reserve(&self, permits: &Semaphore) -> Result<bool> {
+        loop {
+            match permits.try_acquire() {
+                Ok(permit) => {
+                    permit.forget();
+                    return Ok(true);
+                }
+                Err(TryAcquireError::Closed) => return Ok(false),
+                Err(TryAcquireError::NoPermits) => {}
+            }
+
+            match self.policy {
+                OverflowPolicy::Block => {
+                    return match permits.acquire().await {
+                        Ok(permit) => {
+                            permit.forget();
+                            Ok(true)
+                        }
+                        Err(_) => Ok(false),
+                    };
+                }
+                OverflowPolicy::FailFast => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Err(Error::PushChannelFull {
+                        capacity: self.capacity,
+                    });
+                }
+                OverflowPolicy::DropOldest => {
+                    if self.drop_oldest(permits) {
+                        return Ok(true);
+                    }
+                    // the receiver is about to release a permit
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+    }
+
+    /// Drops the oldest non connection state event, so that the new event
+    /// takes its room
+    ///
+    /// Returns `false` if there is no such event in the queue.
+    fn drop_oldest(&self, permits: &Semaphore) -> bool {
+        // the receiver releases the permits with the queue locked
+        let mut queue = self.queue.lock();
+        if let Ok(permit) = permits.try_acquire() {
+            permit.forget();
+            return true;
+        }
+        match queue
+            .iter()
+            .position(|event| !matches!(event, PushEvent::ConnectionState(_)))
+        {
+            Some(index) => {
+                queue.remove(index);
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the counter of the dropped events
+    #[inline]
+    pub(crate) fn dropped(&self) -> Arc<AtomicU64> {
+        self.dropped.clone()
+    }
+
+    /// Returns a sender for creating the subscribers, which does not keep the
+    /// subscribers open after the background task has exited
+    #[inline]
+    pub(crate) fn events_tx(&self) -> broadcast::WeakSender<PushEvent> {
+        self.events_tx.downgrade()
+    }
+}
+
+/// Receiver of the push events returned by
+/// [`TradeContext::try_new`](crate::trade::TradeContext::try_new)
+///
+/// See [`Config::push_capacity`] for the capacity and the overflow policy.
+#[derive(Debug)]
+pub struct PushEventReceiver {
+    queue: Arc<Mutex<VecDeque<PushEvent>>>,
+    wake_rx: flume::Receiver<()>,
+    permits: Option<Arc<Semaphore>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl PushEventReceiver {
+    /// Receives the next event
+    ///
+    /// Returns `None` if the trade context has been closed and all the
+    /// buffered events have been received.
+    pub async fn recv(&mut self) -> Option<PushEvent> {
+        loop {
+            if let Some(event) = self.try_recv() {
+                return Some(event);
+            }
+            self.wake_rx.recv_async().await.ok()?;
+        }
+    }
+
+    /// Blocking version of [`PushEventReceiver::recv`]
+    pub fn blocking_recv(&mut self) -> Option<PushEvent> {
+        loop {
+            if let Some(event) = self.try_recv() {
+                return Some(event);
+            }
+            self.wake_rx.recv().ok()?;
+        }
+    }
+
+    fn try_recv(&self) -> Option<PushEvent> {
+        let mut queue = self.queue.lock();
+        let event = queue.pop_front()?;
+        if let Some(permits) = &self.permits {
+            if !matches!(event, PushEvent::ConnectionState(_)) {
+                permits.add_permits(1);
+            }
+        }
+        Some(event)
+    }
+
+    /// Returns the number of events dropped because of the
+    /// [`OverflowPolicy`]
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Converts the receiver into a [`Stream`]
+    pub fn into_stream(self) -> impl Stream<Item = PushEvent> + Send + Unpin + 'static {
+        Box::pin(stream::unfold(self, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        }))
+    }
+}
+
+impl Drop for PushEventReceiver {
+    fn drop(&mut self) {
+        if let Some(permits) = &self.permits {
+            permits.close();
+        }
+        self.queue.lock().clear();
+    }
+}
+
+/// What a subscriber does when it falls behind and the buffered events it
+/// has not received yet are overwritten
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum LagPolicy {
+    /// Skip the missed events and continue with the oldest buffered event
+    #[default]
+    Skip,
+    /// End the subscription, so that the subscriber can resynchronize
+    Close,
+}
+
+/// Options for subscribe push events
+///
+/// An event passes the filters if it matches all of the non-empty filters.
+/// [`PushEvent::ConnectionState`] events are always delivered.
+#[derive(Debug, Clone, Default)]
+pub struct SubscribeEventsOptions {
+    account_nos: HashSet<String>,
+    symbols: HashSet<Symbol>,
+    order_ids: HashSet<String>,
+    lag_policy: LagPolicy,
+}
+
+impl SubscribeEventsOptions {
+    /// Create a new `SubscribeEventsOptions`
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only receive the events of these accounts
+    #[inline]
+    #[must_use]
+    pub fn account_nos(self, account_nos: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            account_nos: account_nos.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Only receive the events of these symbols
+    #[inline]
+    #[must_use]
+    pub fn symbols(self, symbols: impl IntoIterator<Item = Symbol>) -> Self {
+        Self {
+            symbols: symbols.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Only receive the events of these orders
+    #[inline]
+    #[must_use]
+    pub fn order_ids(self, order_ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            order_ids: order_ids.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Set the lag policy (default: [`LagPolicy::Skip`])
+    #[inline]
+    #[must_use]
+    pub fn lag_policy(self, lag_policy: LagPolicy) -> Self {
+        Self { lag_policy, ..self }
+    }
+
+    fn matches(&self, event: &PushEvent) -> bool {
+        let (account_no, symbol, order_id) = match event {
+            PushEvent::OrderChanged(order_changed) => (
+                Some(order_changed.account_no.as_str()),
+                Some(order_changed.symbol.clone()),
+                Some(order_changed.order_id.as_str()),
+            ),
+            PushEvent::Execution(execution) => (
+                Some(execution.account_no.as_str()),
+                Some(execution.symbol.clone()),
+                Some(execution.order_id.as_str()),
+            ),
+            PushEvent::ConnectionState(_) => return true,
+            PushEvent::Unknown { data, .. } => {
+                let field = |name| data.get(name).and_then(|value| value.as_str());
+                (
+                    field("account_no"),
+                    field("symbol").map(|symbol| Symbol::parse_lenient(symbol.to_string())),
+                    field("order_id"),
+                )
+            }
+        };
+
+        (self.account_nos.is_empty()
+            || account_no.is_some_and(|account_no| self.account_nos.contains(account_no)))
+            && (self.symbols.is_empty()
+                || symbol.is_some_and(|symbol| self.symbols.contains(&symbol)))
+            && (self.order_ids.is_empty()
+                || order_id.is_some_and(|order_id| self.order_ids.contains(order_id)))
+    }
+}
+
+/// Receiver of the push events returned by
+/// [`TradeContext::subscribe_events`](crate::trade::TradeContext::subscribe_events)
+///
+/// Each receiver buffers the events independently, a receiver that falls
+/// behind never blocks the others, see [`LagPolicy`].
+#[derive(Debug)]
+pub struct EventReceiver {
+    rx: broadcast::Receiver<PushEvent>,
+    opts: SubscribeEventsOptions,
+    lagged: u64,
+    closed: bool,
+}
+
+impl EventReceiver {
+    pub(crate) fn new(
+        events_tx: &broadcast::WeakSender<PushEvent>,
+        opts: SubscribeEventsOptions,
+    ) -> Self {
+        let (rx, closed) = match events_tx.upgrade() {
+            Some(events_tx) => (events_tx.subscribe(), false),
+            None => (broadcast::channel(1).1, true),
+        };
+        Self {
+            rx,
+            opts,
+            lagged: 0,
+            closed,
+        }
+    }
+
+    /// Receives the next event that passes the filters
+    ///
+    /// Returns `None` if the trade context has been closed, or if the receiver
+    /// lagged behind with [`LagPolicy::Close`].
+    pub async fn recv(&mut self) -> Option<PushEvent> {
+        while !self.closed {
+            let res = self.rx.recv().await;
+            if let Some(event) = self.handle(res) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Blocking version of [`EventReceiver::recv`]
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution
+    /// context.
+    pub fn blocking_recv(&mut self) -> Option<PushEvent> {
+        while !self.closed {
+            let res = self.rx.blocking_recv();
+            if let Some(event) = self.handle(res) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Returns the number of events missed because the receiver lagged behind
+    #[inline]
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+
+    /// Converts the receiver into a [`Stream`]
+    pub fn into_stream(self) -> impl Stream<Item = PushEvent> + Send + Unpin + 'static {
+        Box::pin(stream::unfold(self, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        }))
+    }
+
+    fn handle(
+        &mut self,
+        res: std::result::Result<PushEvent, broadcast::error::RecvError>,
+    ) -> Option<PushEvent> {
+        match res {
+            Ok(event) if self.opts.matches(&event) => Some(event),
+            Ok(_) => None,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                self.lagged += n;
+                if self.opts.lag_policy == LagPolicy::Close {
+                    self.closed = true;
+                }
+                None
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                self.closed = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unknown_event(account_no: &str, symbol: &str) -> PushEvent {
+        PushEvent::Unknown {
+            topic: "private".to_string(),
+            event: "test".to_string(),
+            data: serde_json::json!({ "account_no": account_no, "symbol": symbol }),
+        }
+    }
+
+    #[tokio::test]
+    async fn filter_events() {
+        let (push_tx, _push_rx) = PushSender::new(&Config::new("", "", "").events_capacity(4));
+        let events_tx = push_tx.events_tx();
+        let mut rx = EventReceiver::new(
+            &events_tx,
+            SubscribeEventsOptions::new()
+                .account_nos(["A"])
+                .symbols(["700.HK".parse().unwrap()]),
+        );
+
+        push_tx.send(unknown_event("B", "700.HK")).await.unwrap();
+        push_tx.send(unknown_event("A", "AAPL.US")).await.unwrap();
+        push_tx.send(unknown_event("A", "700.hk")).await.unwrap();
+        drop(push_tx);
+
+        assert!(matches!(
+            rx.recv().await,
+            Some(PushEvent::Unknown { data, .. }) if data["symbol"] == "700.hk"
+        ));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn lag_policy() {
+        let (push_tx, _push_rx) = PushSender::new(&Config::new("", "", "").events_capacity(4));
+        let events_tx = push_tx.events_tx();
+        let mut skip = EventReceiver::new(&events_tx, SubscribeEventsOptions::new());
+        let mut close = EventReceiver::new(
+            &events_tx,
+            SubscribeEventsOptions::new().lag_policy(LagPolicy::Close),
+        );
+
+        for _ in 0..5 {
+            push_tx.send(unknown_event("A", "700.HK")).await.unwrap();
+        }
+
+        assert!(skip.recv().await.is_some());
+        assert_eq!(skip.lagged(), 1);
+        assert!(close.recv().await.is_none());
+        assert_eq!(close.lagged(), 1);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_state_events() {
+        let config = Config::new("", "", "").push_capacity(2, OverflowPolicy::DropOldest);
+        let (push_tx, mut push_rx) = PushSender::new(&config);
+        push_tx
+            .send(PushEvent::ConnectionState(
+                crate::trade::ConnectionState::Disconnected { reason: None },
+            ))
+            .await
+            .unwrap();
+        for account_no in ["A", "B", "C", "D"] {
+            push_tx
+                .send(unknown_event(account_no, "700.HK"))
+                .await
+                .unwrap();
+        }
+        drop(push_tx);
+
+        assert_eq!(push_rx.dropped(), 2);
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::ConnectionState(
+                crate::trade::ConnectionState::Disconnected { .. }
+            ))
+        ));
+        for account_no in ["C", "D"] {
+            assert!(matches!(
+                push_rx.recv().await,
+                Some(PushEvent::Unknown { data, .. }) if data["account_no"] == account_no
+            ));
+        }
+        assert!(push_rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn overflow_policy() {
+        let config = Config::new("", "", "").push_capacity(2, OverflowPolicy::DropOldest);
+        let (push_tx, mut push_rx) = PushSender::new(&config);
+        for account_no in ["A", "B", "C"] {
+            push_tx
+                .send(unknown_event(account_no, "700.HK"))
+                .await
+                .unwrap();
+        }
+        assert_eq!(push_rx.dropped(), 1);
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::Unknown { data, .. }) if data["account_no"] == "B"
+        ));
+
+        // the connection state events do not take the room of another event
+        push_tx
+            .send(PushEvent::ConnectionState(
+                crate::trade::ConnectionState::Connected,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(push_rx.dropped(), 1);
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::Unknown { data, .. }) if data["account_no"] == "C"
+        ));
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::ConnectionState(_))
+        ));
+
+        let config = Config::new("", "", "").push_capacity(1, OverflowPolicy::FailFast);
+        let (push_tx, mut push_rx) = PushSender::new(&config);
+        push_tx.send(unknown_event("A", "700.HK")).await.unwrap();
+        assert!(matches!(
+            push_tx.send(unknown_event("B", "700.HK")).await,
+            Err(Error::PushChannelFull { capacity: 1 })
+        ));
+        push_tx
+            .send(PushEvent::ConnectionState(
+                crate::trade::ConnectionState::Connected,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(push_rx.dropped(), 1);
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::Unknown { data, .. }) if data["account_no"] == "A"
+        ));
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::ConnectionState(_))
+        ));
+        push_tx.send(unknown_event("C", "700.HK")).await.unwrap();
+
+        let config = Config::new("", "", "").push_capacity(1, OverflowPolicy::Block);
+        let (push_tx, mut push_rx) = PushSender::new(&config);
+        push_tx.send(unknown_event("A", "700.HK")).await.unwrap();
+        push_tx
+            .send(PushEvent::ConnectionState(
+                crate::trade::ConnectionState::Connected,
+            ))
+            .await
+            .unwrap();
+        let blocked = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            push_tx.send(unknown_event("B", "700.HK")),
+        );
+        assert!(blocked.await.is_err());
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::Unknown { .. })
+        ));
+        push_tx.send(unknown_event("B", "700.HK")).await.unwrap();
+        assert!(matches!(
+            push_rx.recv().await,
+            Some(PushEvent::ConnectionState(_))
+        ));
+        drop(push_rx);
+        push_tx.send(unknown_event("C", "700.HK")).await.unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use rust_decimal::Decimal;
+
+use crate::{
+    trade::{
+        events::PushSender, tracker::DEFAULT_RETENTION, PushEvent, PushExecution, PushOrderChanged,
+    },
+    Result,
+};
+
+#[derive(Default)]
+struct Fills {
+    executed_quantity: i64,
+    amount: Decimal,
+    // the duplicated events of a finished order are ignored
+    finished_at: Option<Instant>,
+}
+
+/// Derives the [`PushExecution`] events from the cumulative executed quantity
+/// of the [`PushOrderChanged`] events
+///
+/// The orders in a terminal status are dropped after the retention of
+/// [`OrderTracker`](crate::trade::OrderTracker), the open orders are kept
+/// however long they wait for a fill.
+pub(crate) struct ExecutionTracker {
+    orders: HashMap<String, Fills>,
+    // the finished orders, from the first finished to the last
+    finished: VecDeque<(Instant, String)>,
+    retention: Duration,
+}
+
+impl Default for ExecutionTracker {
+    fn default() -> Self {
+        Self::with_retention(DEFAULT_RETENTION)
+    }
+}
+
+impl ExecutionTracker {
+    pub(crate) fn with_retention(retention: Duration) -> Self {
+        Self {
+            orders: HashMap::new(),
+            finished: VecDeque::new(),
+            retention,
+        }
+    }
+
+    /// Returns the new fill reported by the event, or `None` if the executed
+    /// quantity has not increased since the last event of the order
+    pub(crate) fn ingest(&mut self, event: &PushOrderChanged) -> Option<PushExecution> {
+        let now = Instant::now();
+        self.evict_expired(now);
+
+        let fills = self.orders.entry(event.order_id.clone()).or_default();
+        if fills.finished_at.is_some() {
+            return None;
+        }
+        if event.status.is_terminal() {
+            fills.finished_at = Some(now);
+            self.finished.push_back((now, event.order_id.clone()));
+        }
+        derive_execution(fills, event)
+    }
+
+    /// Drops the orders finished for longer than the retention at `now`
+    fn evict_expired(&mut self, now: Instant) {
+        while let Some((finished_at, _)) = self.finished.front() {
+            if now.saturating_duration_since(*finished_at) < self.retention {
+                break;
+            }
+            let (finished_at, order_id) = self.finished.pop_front().expect("front exists");
+            if matches!(
+                self.orders.get(&order_id),
+                Some(fills) if fills.finished_at == Some(finished_at)
+            ) {
+                self.orders.remove(&order_id);
+            }
+        }
+    }
+
+    /// Sends a push event, an order changed event is followed by the execution
+    /// event derived from it if any
+    ///
+    /// The execution event is sent even if the order changed event is
+    /// dropped, the first error is returned.
+    ///
+    /// Used by both the trade context and the replay context, so that they
+    /// send the same events.
+    pub(crate) async fn dispatch(&mut self, push_tx: &PushSender, event: PushEvent) -> Result<()> {
+        let execution = match &event {
+            PushEvent::OrderChanged(order_changed) => self.ingest(order_changed),
+            _ => None,
+        };
+        let res = push_tx.send(event).await;
+        match execution {
+            Some(execution) => res.and(push_tx.send(PushEvent::Execution(execution)).await),
+            None => res,
+        }
+    }
+}
+
+fn derive_execution(fills: &mut Fills, event: &PushOrderChanged) -> Option<PushExecution> {
+    let quantity = event.executed_quantity - fills.executed_quantity;
+    if quantity <= 0 {
+        return None;
+    }
+
+    // `executed_price` is the average price of all the fills, the price of
+    // this fill is derived from it unless the event reports exactly this
+    // fill in `last_share`/`last_price`
+    let price = match (event.last_share, event.last_price, event.executed_price) {
+        (Some(last_share), Some(last_price), _) if last_share == Decimal::from(quantity) => {
+            last_price
+        }
+        (_, _, Some(executed_price)) => {
+            (executed_price * Decimal::from(event.executed_quantity) - fills.amount)
+                / Decimal::from(quantity)
+        }
+        (_, Some(last_price), None) => last_price,
+        (_, None, None) => event.submitted_price,
+    };
+
+    fills.executed_quantity = event.executed_quantity;
+    fills.amount += price * Decimal::from(quantity);
+    let average_price = event
+        .executed_price
+        .unwrap_or_else(|| fills.amount / Decimal::from(fills.executed_quantity));
+
+    Some(PushExecution {
+        order_id: event.order_id.clone(),
+        account_no: event.account_no.clone(),
+        symbol: event.symbol.clone(),
+        side: event.side,
+        currency: event.currency.clone(),
+        quantity,
+        price,
+        executed_quantity: event.executed_quantity,
+        average_price,
+        remaining_quantity: (event.submitted_quantity - event.executed_quantity).max(0),
+        executed_at: event.updated_at,
+        recovered: event.recovered,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trade::{push_types, OrderStatus};
+
+    fn order_changed(
+        executed_quantity: i64,
+        executed_price: i64,
+        last_fill: Option<(i64, i64)>,
+    ) -> PushOrderChanged {
+        let mut event =
+            push_types::tests::order_changed(OrderStatus::PartialFilled, executed_quantity);
+        event.submitted_quantity = 300;
+        event.executed_price = Some(Decimal::from(executed_price)).filter(|price| !price.is_zero());
+        event.last_share = last_fill.map(|(share, _)| Decimal::from(share));
+        event.last_price = last_fill.map(|(_, price)| Decimal::from(price));
+        event
+    }
+
+    #[test]
+    fn derive_executions() {
+        let mut tracker = ExecutionTracker::default();
+        assert!(tracker.ingest(&order_changed(0, 0, None)).is_none());
+
+        let execution = tracker
+            .ingest(&order_changed(100, 300, Some((100, 300))))
+            .unwrap();
+        assert_eq!(execution.quantity, 100);
+        assert_eq!(execution.price, Decimal::from(300));
+        assert_eq!(execution.remaining_quantity, 200);
+
+        // duplicated push
+        assert!(tracker
+            .ingest(&order_changed(100, 300, Some((100, 300))))
+            .is_none());
+
+        // two fills merged into one push
+        let execution = tracker.ingest(&order_changed(300, 302, None)).unwrap();
+        assert_eq!(execution.quantity, 200);
+        assert_eq!(execution.price, Decimal::from(303));
+        assert_eq!(execution.average_price, Decimal::from(302));
+        assert_eq!(execution.remaining_quantity, 0);
+    }
+
+    #[test]
+    fn first_event_reports_previous_fills() {
+        let mut tracker = ExecutionTracker::default();
+        // filled before the tracker started
+        let execution = tracker.ingest(&order_changed(200, 301, None)).unwrap();
+        assert_eq!(execution.quantity, 200);
+        assert_eq!(execution.price, Decimal::from(301));
+        assert_eq!(execution.remaining_quantity, 100);
+
+        let execution = tracker
+            .ingest(&order_changed(300, 302, Some((100, 304))))
+            .unwrap();
+        assert_eq!(execution.quantity, 100);
+        assert_eq!(execution.price, Decimal::from(304));
+        assert_eq!(execution.average_price, Decimal::from(302));
+    }
+
+    #[test]
+    fn ignore_finished_orders() {
+        let mut tracker = ExecutionTracker::default();
+        let mut filled = order_changed(300, 302, None);
+        filled.status = OrderStatus::Filled;
+
+        assert!(tracker.ingest(&order_changed(0, 0, None)).is_none());
+        assert_eq!(tracker.ingest(&filled).unwrap().quantity, 300);
+        // duplicated push of a finished order
+        assert!(tracker.ingest(&filled).is_none());
+        filled.executed_quantity = 400;
+        assert!(tracker.ingest(&filled).is_none());
+    }
+
+    #[test]
+    fn evict_expired_orders() {
+        let retention = Duration::from_secs(60);
+        let mut tracker = ExecutionTracker::with_retention(retention);
+        let mut filled = order_changed(300, 302, None);
+        filled.status = OrderStatus::Filled;
+        let mut open = order_changed(0, 0, None);
+        open.order_id = "2".to_string();
+
+        assert!(tracker.ingest(&filled).is_some());
+        assert!(tracker.ingest(&open).is_none());
+        let finished_at = Instant::now();
+
+        tracker.evict_expired(finished_at + retention / 2);
+        assert_eq!(tracker.orders.len(), 2);
+
+        // only the finished orders expire, however long the open ones wait
+        tracker.evict_expired(finished_at + retention);
+        assert_eq!(tracker.orders.len(), 1);
+        assert!(tracker.finished.is_empty());
+        open.executed_quantity = 100;
+        assert_eq!(tracker.ingest(&open).unwrap().quantity, 100);
+    }
+}
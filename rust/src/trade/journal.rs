@@ -0,0 +1,332 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    thread::JoinHandle,
+};
+
+use bytes::Bytes;
+use time::OffsetDateTime;
+
+use crate::{JournalConfig, OverflowPolicy};
+
+/// Written at the beginning of each journal file
+const MAGIC: &[u8; 5] = b"LPWJ\x01";
+
+/// Size of the header of a record: timestamp (8), command code (1) and body
+/// length (4)
+const RECORD_HEADER_LEN: usize = 13;
+
+/// A raw push message received by the trade context
+#[derive(Debug)]
+pub(crate) struct Record {
+    pub(crate) received_at: OffsetDateTime,
+    pub(crate) command_code: u8,
+    pub(crate) body: Bytes,
+}
+
+/// Appends the raw push messages to the journal files in a background thread
+///
+/// [`Journal::close`] waits for the pending messages to be written, dropping
+/// the journal does not.
+pub(crate) struct Journal {
+    tx: flume::Sender<Record>,
+    // used to drop the oldest records, only for `DropOldest`
+    rx: Option<flume::Receiver<Record>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: AtomicU64,
+    handle: JoinHandle<()>,
+}
+
+impl Journal {
+    pub(crate) fn open(config: &JournalConfig) -> io::Result<Self> {
+        let mut writer = JournalWriter::open(config.clone())?;
+        let (tx, rx) = flume::bounded::<Record>(config.capacity);
+        let drop_rx = (config.overflow_policy == OverflowPolicy::DropOldest).then(|| rx.clone());
+
+        let handle = std::thread::Builder::new()
+            .name("longportwhale-journal".to_string())
+            .spawn(move || {
+                while let Ok(record) = rx.recv() {
+                    if let Err(err) = writer.write(&record) {
+                        tracing::error!(
+                            error = %err,
+                            path = %writer.config.path.display(),
+                            "failed to write push journal"
+                        );
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            tx,
+            rx: drop_rx,
+            capacity: config.capacity,
+            policy: config.overflow_policy,
+            dropped: AtomicU64::new(0),
+            handle,
+        })
+    }
+
+    /// Queues a message to be written according to the [`OverflowPolicy`]
+    ///
+    /// Returns an error if the message is dropped because of
+    /// [`OverflowPolicy::FailFast`].
+    pub(crate) async fn record(&self, command_code: u8, body: Bytes) -> io::Result<()> {
+        let mut record = Record {
+            received_at: OffsetDateTime::now_utc(),
+            command_code,
+            body,
+        };
+
+        if self.policy == OverflowPolicy::Block {
+            let _ = self.tx.send_async(record).await;
+            return Ok(());
+        }
+
+        loop {
+            match self.tx.try_send(record) {
+                Ok(()) | Err(flume::TrySendError::Disconnected(_)) => return Ok(()),
+                Err(flume::TrySendError::Full(full_record)) => {
+                    let Some(rx) = &self.rx else {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return Err(io::Error::other(format!(
+                            "push journal is full, capacity: {}",
+                            self.capacity
+                        )));
+                    };
+                    if rx.try_recv().is_ok() {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        tracing::warn!("dropped the oldest message of push journal");
+                    }
+                    record = full_record;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of messages dropped because of the
+    /// [`OverflowPolicy`]
+    #[inline]
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the pending messages to be written
+    pub(crate) async fn close(self) {
+        let Self { tx, rx, handle, .. } = self;
+        drop((tx, rx));
+        let _ = tokio::task::spawn_blocking(move || handle.join()).await;
+    }
+}
+
+struct JournalWriter {
+    config: JournalConfig,
+    file: BufWriter<File>,
+    size: u64,
+}
+
+impl JournalWriter {
+    fn open(config: JournalConfig) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)?;
+        let size = file.metadata()?.len();
+        let mut writer = Self {
+            config,
+            file: BufWriter::new(file),
+            size,
+        };
+        if writer.size == 0 {
+            writer.file.write_all(MAGIC)?;
+            writer.file.flush()?;
+            writer.size = MAGIC.len() as u64;
+        }
+        Ok(writer)
+    }
+
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        let len = (RECORD_HEADER_LEN + record.body.len()) as u64;
+        if self.size > MAGIC.len() as u64 && self.size + len > self.config.max_file_size {
+            self.rotate()?;
+        }
+
+        let body_len = u32::try_from(record.body.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "push message too large"))?;
+        self.file
+            .write_all(&(record.received_at.unix_timestamp_nanos() as i64).to_be_bytes())?;
+        self.file.write_all(&[record.command_code])?;
+        self.file.write_all(&body_len.to_be_bytes())?;
+        self.file.write_all(&record.body)?;
+        self.file.flush()?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let path = &self.config.path;
+        let max_files = self.config.max_files;
+
+        if max_files == 0 {
+            fs::remove_file(path)?;
+        } else {
+            remove_if_exists(&rotated_path(path, max_files))?;
+            for n in (1..max_files).rev() {
+                match fs::rename(rotated_path(path, n), rotated_path(path, n + 1)) {
+                    Err(err) if err.kind() != ErrorKind::NotFound => return Err(err),
+                    _ => {}
+                }
+            }
+            fs::rename(path, rotated_path(path, 1))?;
+        }
+
+        *self = Self::open(self.config.clone())?;
+        Ok(())
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the path of the `n`th rotated journal file, e.g. `push.journal.1`
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(format!(".{n}"));
+    path.into()
+}
+
+/// Returns the journal files from the oldest to the newest, the rotated files
+/// followed by the file at `path`
+pub(crate) fn journal_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = (1..)
+        .map(|n| rotated_path(path, n))
+        .take_while(|path| path.exists())
+        .collect::<Vec<_>>();
+    files.reverse();
+    files.push(path.to_path_buf());
+    files
+}
+
+/// Reads the records of a journal file
+pub(crate) struct JournalReader {
+    reader: BufReader<File>,
+}
+
+impl JournalReader {
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("not a push journal: {}", path.display()),
+            ));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Returns the next record, or `None` at the end of the file
+    ///
+    /// A record truncated by a crash while it was written is treated as the
+    /// end of the file.
+    pub(crate) fn next_record(&mut self) -> io::Result<Option<Record>> {
+        let mut header = [0; RECORD_HEADER_LEN];
+        let mut body = Vec::new();
+        let res = self.reader.read_exact(&mut header).and_then(|_| {
+            // the body is not allocated upfront, the length may be corrupted
+            let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+            (&mut self.reader).take(len as u64).read_to_end(&mut body)?;
+            if body.len() < len {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            Ok(())
+        });
+        match res {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => {
+                if header != [0; RECORD_HEADER_LEN] {
+                    tracing::warn!("ignored truncated record at the end of push journal");
+                }
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        }
+
+        let timestamp = i64::from_be_bytes(header[..8].try_into().unwrap());
+        Ok(Some(Record {
+            received_at: OffsetDateTime::from_unix_timestamp_nanos(timestamp as i128)
+                .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?,
+            command_code: header[8],
+            body: body.into(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_rotated_journal() {
+        let dir =
+            std::env::temp_dir().join(format!("longportwhale-journal-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = JournalConfig::new(dir.join("push.journal"))
+            .max_file_size(80)
+            .max_files(2);
+
+        let mut writer = JournalWriter::open(config.clone()).unwrap();
+        for n in 0..8u8 {
+            writer
+                .write(&Record {
+                    received_at: OffsetDateTime::now_utc(),
+                    command_code: 18,
+                    body: vec![n; 20].into(),
+                })
+                .unwrap();
+        }
+        drop(writer);
+
+        // 2 records per file, the 2 oldest files have been removed
+        let files = journal_files(&config.path);
+        assert_eq!(files.len(), 3);
+        let mut bodies = Vec::new();
+        for file in files {
+            let mut reader = JournalReader::open(&file).unwrap();
+            while let Some(record) = reader.next_record().unwrap() {
+                assert_eq!(record.command_code, 18);
+                bodies.push(record.body[0]);
+            }
+        }
+        assert_eq!(bodies, [2, 3, 4, 5, 6, 7]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupted_record_length() {
+        let path = std::env::temp_dir().join(format!(
+            "longportwhale-journal-corrupted-{}",
+            std::process::id()
+        ));
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&[0; 9]);
+        data.extend_from_slice(&u32::MAX.to_be_bytes());
+        data.extend_from_slice(b"body");
+        fs::write(&path, data).unwrap();
+
+        let mut reader = JournalReader::open(&path).unwrap();
+        assert!(reader.next_record().unwrap().is_none());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
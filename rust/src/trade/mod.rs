@@ -3,9 +3,35 @@
 mod cmd_code;
 mod context;
 mod core;
+mod events;
+mod executions;
+mod journal;
 mod push_types;
+mod replay;
+mod requests;
+mod tracker;
 mod types;
 
-pub use context::TradeContext;
-pub use push_types::{PushEvent, PushOrderChanged, TopicType};
-pub use types::{OrderSide, OrderStatus, OrderTag, OrderType, TriggerStatus};
+pub use context::{SubmitOrderResponse, TradeContext};
+pub use events::{EventReceiver, LagPolicy, PushEventReceiver, SubscribeEventsOptions};
+pub use push_types::{ConnectionState, PushEvent, PushExecution, PushOrderChanged, TopicType};
+pub use replay::ReplayContext;
+pub use requests::{
+    CashFlowOptions, EstimateMaxPurchaseQuantityOptions, GetHistoryExecutionsOptions,
+    GetHistoryOrdersOptions, GetTodayExecutionsOptions, GetTodayOrdersOptions, ReplaceOrderOptions,
+    SubmitOrderOptions, SubmitOrderOptionsBuilder,
+};
+pub use tracker::OrderTracker;
+pub use types::{
+    AccountBalance, BalanceType, CashFlow, CashFlowDirection, CashInfo,
+    EstimateMaxPurchaseQuantityResponse, Execution, FundPosition, FundPositionChannel, MarginRatio,
+    Order, OrderSide, OrderStatus, OrderTag, OrderType, OutsideRTH, StockPosition,
+    StockPositionChannel, TimeInForceType, TriggerStatus,
+};
+
+/// Parses the body of a push message, only for the benchmarks
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub fn bench_parse_push(command_code: u8, body: bytes::Bytes) -> crate::Result<PushEvent> {
+    PushEvent::parse(command_code, body)
+}
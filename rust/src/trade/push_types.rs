@@ -1,16 +1,20 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr, sync::Arc};
 
-use longport_proto::trade::Notification;
+use bytes::Bytes;
+use longportwhale_wscli::WsCloseReason;
 use prost::Message;
 use rust_decimal::Decimal;
-use serde::Deserialize;
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
 use strum_macros::{Display, EnumString};
 use time::OffsetDateTime;
 
 use crate::{
     serde_utils,
-    trade::{cmd_code, OrderSide, OrderStatus, OrderTag, OrderType, TriggerStatus},
-    Error, Result,
+    trade::{cmd_code, Order, OrderSide, OrderStatus, OrderTag, OrderType, TriggerStatus},
+    Error, Result, Symbol,
 };
 
 /// Topic type
@@ -22,7 +26,7 @@ pub enum TopicType {
 }
 
 /// Order changed message
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct PushOrderChanged {
     /// Order side
     pub side: OrderSide,
@@ -32,7 +36,8 @@ pub struct PushOrderChanged {
     #[serde(with = "serde_utils::int64_str")]
     pub submitted_quantity: i64,
     /// Order symbol
-    pub symbol: String,
+    #[serde(with = "serde_utils::symbol_lenient")]
+    pub symbol: Symbol,
     /// Order type
     pub order_type: OrderType,
     /// Submitted price
@@ -87,30 +92,338 @@ pub struct PushOrderChanged {
     pub last_price: Option<Decimal>,
     /// Remark message
     pub remark: String,
+    /// `true` if this event was not pushed by the server, but synthesized
+    /// from the order list fetched after a reconnect
+    ///
+    /// See [`Config::recover_orders`](crate::Config::recover_orders).
+    #[serde(skip)]
+    pub recovered: bool,
+}
+
+impl PushOrderChanged {
+    /// Synthesize a recovered event from the current state of an order
+    pub(crate) fn recovered(order: Order) -> Self {
+        Self {
+            side: order.side,
+            stock_name: order.stock_name,
+            submitted_quantity: order.quantity,
+            symbol: order.symbol,
+            order_type: order.order_type,
+            submitted_price: order.price.unwrap_or_default(),
+            executed_quantity: order.executed_quantity,
+            executed_price: order.executed_price,
+            order_id: order.order_id,
+            currency: order.currency,
+            status: order.status,
+            submitted_at: order.submitted_at,
+            updated_at: order.updated_at.unwrap_or(order.submitted_at),
+            trigger_price: order.trigger_price,
+            msg: order.msg,
+            tag: order.tag,
+            trigger_status: order.trigger_status,
+            trigger_at: order.trigger_at,
+            trailing_amount: order.trailing_amount,
+            trailing_percent: order.trailing_percent,
+            limit_offset: order.limit_offset,
+            account_no: order.account_no,
+            last_share: None,
+            last_price: order.last_done,
+            remark: order.remark,
+            recovered: true,
+        }
+    }
+}
+
+/// A new fill of an order, derived from the [`PushOrderChanged`] events
+///
+/// Exactly one event is sent for each increase of the executed quantity of an
+/// order, the duplicated pushes are ignored. If several fills are merged into
+/// one push (e.g. the events recovered after a reconnect), they are reported
+/// as one fill at their average price.
+///
+/// The fills already made when the first event of an order is received, e.g.
+/// an order partially filled before the trade context started, are reported
+/// as one fill at their average price.
+#[derive(Debug, Clone)]
+pub struct PushExecution {
+    /// Order ID
+    pub order_id: String,
+    /// Account no
+    pub account_no: String,
+    /// Order symbol
+    pub symbol: Symbol,
+    /// Order side
+    pub side: OrderSide,
+    /// Currency
+    pub currency: String,
+    /// Quantity of this fill
+    pub quantity: i64,
+    /// Price of this fill
+    pub price: Decimal,
+    /// Cumulative executed quantity, including this fill
+    pub executed_quantity: i64,
+    /// Average price of all the fills of the order, including this fill
+    pub average_price: Decimal,
+    /// Quantity remaining to be filled
+    pub remaining_quantity: i64,
+    /// Time of the order update that reported this fill
+    pub executed_at: OffsetDateTime,
+    /// `true` if the fill was derived from a recovered event, see
+    /// [`PushOrderChanged::recovered`]
+    pub recovered: bool,
+}
+
+/// Connection state of the trade context
+#[derive(Debug, Clone)]
+pub enum ConnectionState {
+    /// Connecting to the trade server
+    Connecting,
+    /// The websocket connection has been established
+    Connected,
+    /// The session has been authenticated, push events can be received
+    Authenticated,
+    /// The connection has been lost
+    Disconnected {
+        /// The close frame sent by the server, if any
+        reason: Option<WsCloseReason>,
+    },
+    /// Reconnecting to the trade server
+    Reconnecting {
+        /// Reconnect attempt number, starting from `1`
+        attempt: u32,
+    },
+    /// The trade context has been closed and will not reconnect, this is
+    /// always the last event sent to the push event receiver
+    Closed {
+        /// The error that closed the trade context, e.g.
+        /// [`Error::ReconnectGaveUp`]
+        error: Option<Arc<Error>>,
+    },
 }
 
 /// Push event
-#[derive(Debug, Deserialize)]
-#[serde(tag = "event", content = "data")]
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum PushEvent {
     /// Order changed
-    #[serde(rename = "order_changed_lb")]
     OrderChanged(PushOrderChanged),
+    /// New fill of an order, sent right after the [`PushEvent::OrderChanged`]
+    /// event that reported it
+    Execution(PushExecution),
+    /// Connection state changed
+    ///
+    /// Push events may have been missed while the state was not
+    /// [`ConnectionState::Authenticated`].
+    ConnectionState(ConnectionState),
+    /// A push event not known by this version of the SDK
+    Unknown {
+        /// Topic of the notification
+        topic: String,
+        /// Event name
+        event: String,
+        /// Event data
+        data: serde_json::Value,
+    },
 }
 
-impl PushEvent {
-    pub(crate) fn parse(command_code: u8, data: &[u8]) -> Result<Option<PushEvent>> {
-        if command_code == cmd_code::PUSH_NOTIFICATION {
-            let notification = Notification::decode(data)?;
-            if let Ok(TopicType::Private) = TopicType::from_str(&notification.topic) {
-                Ok(Some(serde_json::from_slice::<PushEvent>(
-                    &notification.data,
-                )?))
-            } else {
-                Ok(None)
+/// [`Notification`](longport_proto::trade::Notification) of which the data
+/// shares the buffer of the push message instead of being copied
+#[derive(Clone, PartialEq, Message)]
+struct Notification {
+    #[prost(string, tag = "1")]
+    topic: String,
+    #[prost(bytes = "bytes", tag = "4")]
+    data: Bytes,
+}
+
+/// Deserializes the JSON data of a notification, `{"event": ..., "data": ...}`,
+/// into a [`PushEvent`] in a single pass
+struct NotificationData<'a> {
+    topic: &'a str,
+}
+
+impl NotificationData<'_> {
+    fn is_order_changed(&self, event: &str) -> bool {
+        TopicType::from_str(self.topic) == Ok(TopicType::Private) && event == "order_changed_lb"
+    }
+
+    fn into_event(self, event: String, data: EventData) -> serde_json::Result<PushEvent> {
+        Ok(match data {
+            EventData::OrderChanged(order_changed) => PushEvent::OrderChanged(order_changed),
+            // the data came before the event name
+            EventData::Value(data) if self.is_order_changed(&event) => {
+                PushEvent::OrderChanged(serde_json::from_value(data)?)
             }
-        } else {
-            Err(Error::UnknownCommand(command_code))
+            EventData::Value(data) => PushEvent::Unknown {
+                topic: self.topic.to_string(),
+                event,
+                data,
+            },
+        })
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+enum EventData {
+    OrderChanged(PushOrderChanged),
+    Value(serde_json::Value),
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Event,
+    Data,
+    #[serde(other)]
+    Other,
+}
+
+impl<'de> DeserializeSeed<'de> for NotificationData<'_> {
+    type Value = PushEvent;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for NotificationData<'_> {
+    type Value = PushEvent;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("push notification data")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut event: Option<String> = None;
+        let mut data = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Event => event = Some(map.next_value()?),
+                Field::Data => {
+                    data = Some(match &event {
+                        Some(event) if self.is_order_changed(event) => {
+                            EventData::OrderChanged(map.next_value()?)
+                        }
+                        _ => EventData::Value(map.next_value()?),
+                    })
+                }
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        let event = event.ok_or_else(|| serde::de::Error::missing_field("event"))?;
+        let data = match data {
+            Some(data) => data,
+            None if self.is_order_changed(&event) => {
+                return Err(serde::de::Error::missing_field("data"))
+            }
+            None => EventData::Value(serde_json::Value::Null),
+        };
+        self.into_event(event, data)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl PushEvent {
+    /// Parses the body of a push message received from the websocket
+    ///
+    /// The data of the known events is deserialized directly into the event,
+    /// without going through a [`serde_json::Value`].
+    pub(crate) fn parse(command_code: u8, data: Bytes) -> Result<PushEvent> {
+        if command_code != cmd_code::PUSH_NOTIFICATION {
+            return Err(Error::UnknownCommand(command_code));
+        }
+
+        let notification = Notification::decode(data)?;
+        let mut deserializer = serde_json::Deserializer::from_slice(&notification.data);
+        let event = NotificationData {
+            topic: &notification.topic,
         }
+        .deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Data of an order changed event of the order `1`
+    pub(crate) const ORDER_CHANGED: &str = include_str!("fixtures/order_changed.json");
+
+    /// Returns the data of an order changed event of the order `1`
+    pub(crate) fn order_changed_json(
+        status: OrderStatus,
+        executed_quantity: i64,
+    ) -> serde_json::Value {
+        let mut data: serde_json::Value = serde_json::from_str(ORDER_CHANGED).unwrap();
+        data["status"] = status.to_string().into();
+        data["executed_quantity"] = executed_quantity.to_string().into();
+        data
+    }
+
+    /// Returns an order changed event of the order `1`
+    pub(crate) fn order_changed(status: OrderStatus, executed_quantity: i64) -> PushOrderChanged {
+        serde_json::from_value(order_changed_json(status, executed_quantity)).unwrap()
+    }
+
+    pub(crate) fn notification(topic: &str, data: &str) -> Bytes {
+        longport_proto::trade::Notification {
+            topic: topic.to_string(),
+            data: data.as_bytes().to_vec(),
+            ..Default::default()
+        }
+        .encode_to_vec()
+        .into()
+    }
+
+    #[test]
+    fn parse_order_changed() {
+        let data = order_changed_json(OrderStatus::Filled, 200);
+        for data in [
+            format!(r#"{{"event": "order_changed_lb", "data": {data}}}"#),
+            format!(r#"{{"data": {data}, "event": "order_changed_lb"}}"#),
+        ] {
+            let event =
+                PushEvent::parse(cmd_code::PUSH_NOTIFICATION, notification("private", &data));
+            assert!(matches!(
+                event,
+                Ok(PushEvent::OrderChanged(order_changed))
+                    if order_changed.status == OrderStatus::Filled
+                        && order_changed.executed_quantity == 200
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_unknown_event() {
+        for data in [
+            r#"{"event": "unknown", "data": {"a": 1}}"#,
+            r#"{"data": {"a": 1}, "event": "unknown"}"#,
+        ] {
+            let event =
+                PushEvent::parse(cmd_code::PUSH_NOTIFICATION, notification("private", data));
+            assert!(matches!(
+                event,
+                Ok(PushEvent::Unknown { topic, event, data })
+                    if topic == "private" && event == "unknown" && data["a"] == 1
+            ));
+        }
+
+        let event = PushEvent::parse(
+            cmd_code::PUSH_NOTIFICATION,
+            notification("private", r#"{"event": "order_changed_lb"}"#),
+        );
+        assert!(event.is_err());
     }
 }
@@ -0,0 +1,229 @@
+use std::{io, path::PathBuf, sync::Arc};
+
+use time::OffsetDateTime;
+use tokio::{sync::broadcast, time::Instant};
+
+use crate::{
+    trade::{
+        events::PushSender,
+        executions::ExecutionTracker,
+        journal::{journal_files, JournalReader},
+        ConnectionState, EventReceiver, PushEvent, PushEventReceiver, SubscribeEventsOptions,
+    },
+    Error, OverflowPolicy, Result,
+};
+
+/// Capacity of the push event channel of the replay context, the replay waits
+/// when it is full, and number of events buffered for the subscribers
+const REPLAY_CAPACITY: usize = 1024;
+
+/// Replays the push messages recorded by the journal of a trade context, see
+/// [`Config::journal`](crate::Config::journal)
+///
+/// The messages are parsed into the same [`PushEvent`]s as the trade context
+/// would have sent, including the derived [`PushEvent::Execution`] events, and
+/// sent to the push event receiver and to the subscribers. The events
+/// synthesized by the trade context itself, such as the connection states and
+/// the recovered orders, are not recorded, except that the last event is
+/// always [`ConnectionState::Closed`].
+///
+/// The rotated journal files are replayed first, from the oldest to the
+/// newest.
+///
+/// # Examples
+///
+/// ```no_run
+/// use longportwhale::trade::{PushEvent, ReplayContext};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let (ctx, mut receiver) = ReplayContext::new("push.journal");
+/// tokio::spawn(ctx.run());
+///
+/// while let Some(event) = receiver.recv().await {
+///     println!("{:?}", event);
+/// }
+/// # });
+/// ```
+pub struct ReplayContext {
+    path: PathBuf,
+    speed: Option<f64>,
+    push_tx: PushSender,
+    events_tx: broadcast::WeakSender<PushEvent>,
+}
+
+impl ReplayContext {
+    /// Create a `ReplayContext` that replays the journal at `path`
+    pub fn new(path: impl Into<PathBuf>) -> (Self, PushEventReceiver) {
+        let (push_tx, push_rx) = PushSender::with_capacity(
+            Some(REPLAY_CAPACITY),
+            OverflowPolicy::Block,
+            REPLAY_CAPACITY,
+        );
+        let events_tx = push_tx.events_tx();
+        (
+            Self {
+                path: path.into(),
+                speed: None,
+                push_tx,
+                events_tx,
+            },
+            push_rx,
+        )
+    }
+
+    /// Replays the messages at their recorded pace, accelerated by `speed`,
+    /// e.g. `2.0` replays twice as fast
+    ///
+    /// Default: as fast as the push event receiver receives them
+    #[must_use]
+    pub fn speed(self, speed: f64) -> Self {
+        Self {
+            speed: Some(speed).filter(|speed| *speed > 0.0),
+            ..self
+        }
+    }
+
+    /// Subscribe to the push events with filters, see
+    /// [`TradeContext::subscribe_events`](crate::trade::TradeContext::subscribe_events)
+    ///
+    /// Must be called before [`ReplayContext::run`]. Without
+    /// [`ReplayContext::speed`], the replay is only paced by the push event
+    /// receiver, so a slower subscriber may lag behind.
+    pub fn subscribe_events(&self, opts: SubscribeEventsOptions) -> EventReceiver {
+        EventReceiver::new(&self.events_tx, opts)
+    }
+
+    /// Replays the journal until the end, the receivers are closed after the
+    /// last event
+    ///
+    /// Returns an error if a journal file cannot be read, the replayed events
+    /// are still delivered, followed by [`ConnectionState::Closed`] with the
+    /// same error.
+    pub async fn run(self) -> Result<()> {
+        let (record_tx, record_rx) = flume::bounded(REPLAY_CAPACITY);
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            for file in journal_files(&path) {
+                let res = JournalReader::open(&file).and_then(|mut reader| {
+                    while let Some(record) = reader.next_record()? {
+                        if record_tx.send(Ok(record)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(())
+                });
+                if let Err(err) = res {
+                    let _ = record_tx.send(Err(err));
+                    return;
+                }
+            }
+        });
+
+        let mut executions = ExecutionTracker::default();
+        let mut started: Option<(Instant, OffsetDateTime)> = None;
+        let mut res = Ok(());
+
+        while let Ok(record) = record_rx.recv_async().await {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    res = Err(err);
+                    break;
+                }
+            };
+
+            if let Some(speed) = self.speed {
+                let (started_at, first_received_at) =
+                    *started.get_or_insert((Instant::now(), record.received_at));
+                let elapsed = (record.received_at - first_received_at).max(time::Duration::ZERO);
+                tokio::time::sleep_until(started_at + elapsed.unsigned_abs().div_f64(speed)).await;
+            }
+
+            match PushEvent::parse(record.command_code, record.body) {
+                Ok(event) => {
+                    let _ = executions.dispatch(&self.push_tx, event).await;
+                }
+                Err(err) => {
+                    tracing::error!(error = %err, "failed to parse push message")
+                }
+            }
+        }
+
+        let error = res.as_ref().err().map(|err: &io::Error| {
+            Arc::new(Error::Io(io::Error::new(err.kind(), err.to_string())))
+        });
+        let _ = self
+            .push_tx
+            .send(PushEvent::ConnectionState(ConnectionState::Closed {
+                error,
+            }))
+            .await;
+        res.map_err(Error::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::{
+        trade::{journal::Journal, push_types, OrderStatus},
+        JournalConfig,
+    };
+
+    fn order_changed(executed_quantity: i64) -> Bytes {
+        let data =
+            push_types::tests::order_changed_json(OrderStatus::PartialFilled, executed_quantity);
+        push_types::tests::notification(
+            "private",
+            &format!(r#"{{"event": "order_changed_lb", "data": {data}}}"#),
+        )
+    }
+
+    #[tokio::test]
+    async fn replay_journal() {
+        let dir = std::env::temp_dir().join(format!("longportwhale-replay-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("push.journal");
+
+        let journal = Journal::open(&JournalConfig::new(&path)).unwrap();
+        for executed_quantity in [100, 100, 200] {
+            journal
+                .record(18, order_changed(executed_quantity))
+                .await
+                .unwrap();
+        }
+        journal.close().await;
+
+        let (ctx, mut receiver) = ReplayContext::new(&path);
+        let mut subscriber = ctx.subscribe_events(SubscribeEventsOptions::new());
+        ctx.run().await.unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = receiver.recv().await {
+            events.push(event);
+        }
+        assert!(matches!(
+            events.as_slice(),
+            [
+                PushEvent::OrderChanged(_),
+                PushEvent::Execution(first),
+                PushEvent::OrderChanged(_),
+                PushEvent::OrderChanged(_),
+                PushEvent::Execution(second),
+                PushEvent::ConnectionState(ConnectionState::Closed { error: None }),
+            ] if first.quantity == 100 && second.quantity == 100 && second.remaining_quantity == 0
+        ));
+
+        let mut count = 0;
+        while subscriber.recv().await.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, events.len());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::{serde_utils, trade::BalanceType, Symbol};
+
+/// Options for get cash flow request
+#[derive(Debug, Clone, Serialize)]
+pub struct CashFlowOptions {
+    account_no: String,
+    #[serde(with = "serde_utils::timestamp")]
+    start_time: OffsetDateTime,
+    #[serde(with = "serde_utils::timestamp")]
+    end_time: OffsetDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_type: Option<BalanceType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<Symbol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+}
+
+impl CashFlowOptions {
+    /// Create a new `CashFlowOptions`
+    #[inline]
+    pub fn new(
+        account_no: impl Into<String>,
+        start_time: OffsetDateTime,
+        end_time: OffsetDateTime,
+    ) -> Self {
+        Self {
+            account_no: account_no.into(),
+            start_time,
+            end_time,
+            business_type: None,
+            symbol: None,
+            page: None,
+            size: None,
+        }
+    }
+
+    /// Set the business type
+    #[inline]
+    #[must_use]
+    pub fn business_type(self, business_type: BalanceType) -> Self {
+        Self {
+            business_type: Some(business_type),
+            ..self
+        }
+    }
+
+    /// Set the security symbol
+    #[inline]
+    #[must_use]
+    pub fn symbol(self, symbol: Symbol) -> Self {
+        Self {
+            symbol: Some(symbol),
+            ..self
+        }
+    }
+
+    /// Set the page number (starting from 1)
+    #[inline]
+    #[must_use]
+    pub fn page(self, page: usize) -> Self {
+        Self {
+            page: Some(page),
+            ..self
+        }
+    }
+
+    /// Set the page size
+    #[inline]
+    #[must_use]
+    pub fn size(self, size: usize) -> Self {
+        Self {
+            size: Some(size),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::{serde_utils, Symbol};
+
+/// Options for get history executions request
+#[derive(Debug, Clone, Serialize)]
+pub struct GetHistoryExecutionsOptions {
+    account_no: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<Symbol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "serde_utils::timestamp_opt"
+    )]
+    start_at: Option<OffsetDateTime>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "serde_utils::timestamp_opt"
+    )]
+    end_at: Option<OffsetDateTime>,
+}
+
+impl GetHistoryExecutionsOptions {
+    /// Create a new `GetHistoryExecutionsOptions`
+    #[inline]
+    pub fn new(account_no: impl Into<String>) -> Self {
+        Self {
+            account_no: account_no.into(),
+            symbol: None,
+            order_id: None,
+            start_at: None,
+            end_at: None,
+        }
+    }
+
+    /// Set the security symbol
+    #[inline]
+    #[must_use]
+    pub fn symbol(self, symbol: Symbol) -> Self {
+        Self {
+            symbol: Some(symbol),
+            ..self
+        }
+    }
+
+    /// Set the order id
+    #[inline]
+    #[must_use]
+    pub fn order_id(self, order_id: impl Into<String>) -> Self {
+        Self {
+            order_id: Some(order_id.into()),
+            ..self
+        }
+    }
+
+    /// Set the start time
+    #[inline]
+    #[must_use]
+    pub fn start_at(self, start_at: OffsetDateTime) -> Self {
+        Self {
+            start_at: Some(start_at),
+            ..self
+        }
+    }
+
+    /// Set the end time
+    #[inline]
+    #[must_use]
+    pub fn end_at(self, end_at: OffsetDateTime) -> Self {
+        Self {
+            end_at: Some(end_at),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+use crate::Symbol;
+
+/// Options for get today executions request
+#[derive(Debug, Clone, Serialize)]
+pub struct GetTodayExecutionsOptions {
+    account_no: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<Symbol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+}
+
+impl GetTodayExecutionsOptions {
+    /// Create a new `GetTodayExecutionsOptions`
+    #[inline]
+    pub fn new(account_no: impl Into<String>) -> Self {
+        Self {
+            account_no: account_no.into(),
+            symbol: None,
+            order_id: None,
+        }
+    }
+
+    /// Set the security symbol
+    #[inline]
+    #[must_use]
+    pub fn symbol(self, symbol: Symbol) -> Self {
+        Self {
+            symbol: Some(symbol),
+            ..self
+        }
+    }
+
+    /// Set the order id
+    #[inline]
+    #[must_use]
+    pub fn order_id(self, order_id: impl Into<String>) -> Self {
+        Self {
+            order_id: Some(order_id.into()),
+            ..self
+        }
+    }
+}
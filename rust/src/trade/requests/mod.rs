@@ -0,0 +1,17 @@
+mod estimate_max_purchase_quantity;
+mod get_cash_flow;
+mod get_history_executions;
+mod get_history_orders;
+mod get_today_executions;
+mod get_today_orders;
+mod replace_order;
+mod submit_order;
+
+pub use estimate_max_purchase_quantity::EstimateMaxPurchaseQuantityOptions;
+pub use get_cash_flow::CashFlowOptions;
+pub use get_history_executions::GetHistoryExecutionsOptions;
+pub use get_history_orders::GetHistoryOrdersOptions;
+pub use get_today_executions::GetTodayExecutionsOptions;
+pub use get_today_orders::GetTodayOrdersOptions;
+pub use replace_order::ReplaceOrderOptions;
+pub use submit_order::{SubmitOrderOptions, SubmitOrderOptionsBuilder};
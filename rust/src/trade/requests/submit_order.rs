@@ -0,0 +1,305 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use time::Date;
+
+use crate::{
+    serde_utils,
+    trade::{OrderSide, OrderType, OutsideRTH, TimeInForceType},
+    Error, Result, Symbol,
+};
+
+/// Options for submit order request, created with
+/// [`SubmitOrderOptions::builder`] and checked by
+/// [`SubmitOrderOptionsBuilder::build`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct SubmitOrderOptions(SubmitOrderOptionsBuilder);
+
+/// Builder of [`SubmitOrderOptions`]
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmitOrderOptionsBuilder {
+    account_no: String,
+    symbol: Symbol,
+    order_type: OrderType,
+    side: OrderSide,
+    #[serde(with = "serde_utils::int64_str")]
+    submitted_quantity: i64,
+    time_in_force: TimeInForceType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submitted_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_offset: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trailing_amount: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trailing_percent: Option<Decimal>,
+    #[serde(with = "serde_utils::date_opt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expire_date: Option<Date>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outside_rth: Option<OutsideRTH>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remark: Option<String>,
+}
+
+impl SubmitOrderOptions {
+    /// Create a new `SubmitOrderOptionsBuilder` with the required fields
+    #[inline]
+    pub fn builder(
+        account_no: impl Into<String>,
+        symbol: Symbol,
+        order_type: OrderType,
+        side: OrderSide,
+        submitted_quantity: i64,
+        time_in_force: TimeInForceType,
+    ) -> SubmitOrderOptionsBuilder {
+        SubmitOrderOptionsBuilder {
+            account_no: account_no.into(),
+            symbol,
+            order_type,
+            side,
+            submitted_quantity,
+            time_in_force,
+            submitted_price: None,
+            trigger_price: None,
+            limit_offset: None,
+            trailing_amount: None,
+            trailing_percent: None,
+            expire_date: None,
+            outside_rth: None,
+            remark: None,
+        }
+    }
+}
+
+impl SubmitOrderOptionsBuilder {
+    /// Set the submitted price
+    #[inline]
+    #[must_use]
+    pub fn submitted_price(self, submitted_price: Decimal) -> Self {
+        Self {
+            submitted_price: Some(submitted_price),
+            ..self
+        }
+    }
+
+    /// Set the trigger price
+    #[inline]
+    #[must_use]
+    pub fn trigger_price(self, trigger_price: Decimal) -> Self {
+        Self {
+            trigger_price: Some(trigger_price),
+            ..self
+        }
+    }
+
+    /// Set the limit offset
+    #[inline]
+    #[must_use]
+    pub fn limit_offset(self, limit_offset: Decimal) -> Self {
+        Self {
+            limit_offset: Some(limit_offset),
+            ..self
+        }
+    }
+
+    /// Set the trailing amount
+    #[inline]
+    #[must_use]
+    pub fn trailing_amount(self, trailing_amount: Decimal) -> Self {
+        Self {
+            trailing_amount: Some(trailing_amount),
+            ..self
+        }
+    }
+
+    /// Set the trailing percent
+    #[inline]
+    #[must_use]
+    pub fn trailing_percent(self, trailing_percent: Decimal) -> Self {
+        Self {
+            trailing_percent: Some(trailing_percent),
+            ..self
+        }
+    }
+
+    /// Set the expire date
+    #[inline]
+    #[must_use]
+    pub fn expire_date(self, expire_date: Date) -> Self {
+        Self {
+            expire_date: Some(expire_date),
+            ..self
+        }
+    }
+
+    /// Enable or disable outside regular trading hours
+    #[inline]
+    #[must_use]
+    pub fn outside_rth(self, outside_rth: OutsideRTH) -> Self {
+        Self {
+            outside_rth: Some(outside_rth),
+            ..self
+        }
+    }
+
+    /// Set the remark
+    #[inline]
+    #[must_use]
+    pub fn remark(self, remark: impl Into<String>) -> Self {
+        Self {
+            remark: Some(remark.into()),
+            ..self
+        }
+    }
+
+    /// Checks that the submitted quantity is positive, that the order type is
+    /// known, and that all the fields required by the order type and the time
+    /// in force type are present
+    ///
+    /// Returns [`Error::InvalidField`] for an invalid quantity or order type,
+    /// and [`Error::MissingField`] for the first missing field.
+    pub fn build(self) -> Result<SubmitOrderOptions> {
+        let order_type = self.order_type;
+        let require = |name: &'static str, present: bool| {
+            if present {
+                Ok(())
+            } else {
+                Err(Error::MissingField {
+                    name,
+                    required_by: format!("order type {order_type}"),
+                })
+            }
+        };
+
+        if self.submitted_quantity <= 0 {
+            return Err(Error::InvalidField {
+                name: "submitted_quantity",
+                reason: format!("{} is not positive", self.submitted_quantity),
+            });
+        }
+
+        match order_type {
+            OrderType::LO | OrderType::ELO | OrderType::ALO | OrderType::ODD | OrderType::SLO => {
+                require("submitted_price", self.submitted_price.is_some())?;
+            }
+            OrderType::LIT => {
+                require("submitted_price", self.submitted_price.is_some())?;
+                require("trigger_price", self.trigger_price.is_some())?;
+            }
+            OrderType::MIT => {
+                require("trigger_price", self.trigger_price.is_some())?;
+            }
+            OrderType::TSLPAMT => {
+                require("trailing_amount", self.trailing_amount.is_some())?;
+                require("limit_offset", self.limit_offset.is_some())?;
+            }
+            OrderType::TSLPPCT => {
+                require("trailing_percent", self.trailing_percent.is_some())?;
+                require("limit_offset", self.limit_offset.is_some())?;
+            }
+            OrderType::TSMAMT => {
+                require("trailing_amount", self.trailing_amount.is_some())?;
+            }
+            OrderType::TSMPCT => {
+                require("trailing_percent", self.trailing_percent.is_some())?;
+            }
+            OrderType::MO | OrderType::AO => {}
+            OrderType::Unknown => {
+                return Err(Error::InvalidField {
+                    name: "order_type",
+                    reason: "unknown order type".to_string(),
+                });
+            }
+        }
+
+        if self.time_in_force == TimeInForceType::GoodTilDate && self.expire_date.is_none() {
+            return Err(Error::MissingField {
+                name: "expire_date",
+                required_by: format!("time in force {}", self.time_in_force),
+            });
+        }
+
+        Ok(SubmitOrderOptions(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(order_type: OrderType) -> SubmitOrderOptionsBuilder {
+        SubmitOrderOptions::builder(
+            "A",
+            "700.HK".parse().unwrap(),
+            order_type,
+            OrderSide::Buy,
+            100,
+            TimeInForceType::Day,
+        )
+    }
+
+    #[test]
+    fn build_checks_required_fields() {
+        assert!(options(OrderType::MO).build().is_ok());
+        for submitted_quantity in [0, -100] {
+            assert!(matches!(
+                SubmitOrderOptions::builder(
+                    "A",
+                    "700.HK".parse().unwrap(),
+                    OrderType::MO,
+                    OrderSide::Buy,
+                    submitted_quantity,
+                    TimeInForceType::Day,
+                )
+                .build(),
+                Err(Error::InvalidField {
+                    name: "submitted_quantity",
+                    ..
+                })
+            ));
+        }
+        assert!(matches!(
+            options(OrderType::Unknown).build(),
+            Err(Error::InvalidField {
+                name: "order_type",
+                ..
+            })
+        ));
+        assert!(matches!(
+            options(OrderType::LIT)
+                .submitted_price(decimal!(300i32))
+                .build(),
+            Err(Error::MissingField {
+                name: "trigger_price",
+                ..
+            })
+        ));
+        assert!(matches!(
+            options(OrderType::TSLPAMT)
+                .trailing_amount(decimal!(1i32))
+                .build(),
+            Err(Error::MissingField {
+                name: "limit_offset",
+                ..
+            })
+        ));
+        assert!(matches!(
+            SubmitOrderOptions::builder(
+                "A",
+                "700.HK".parse().unwrap(),
+                OrderType::MO,
+                OrderSide::Buy,
+                100,
+                TimeInForceType::GoodTilDate,
+            )
+            .build(),
+            Err(Error::MissingField {
+                name: "expire_date",
+                ..
+            })
+        ));
+    }
+}
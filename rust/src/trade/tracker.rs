@@ -0,0 +1,347 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+
+use crate::trade::{EventReceiver, PushEvent, PushOrderChanged};
+
+/// How long the orders in a terminal status are kept by default
+pub(crate) const DEFAULT_RETENTION: Duration = Duration::from_secs(300);
+
+struct TrackedOrder {
+    order: PushOrderChanged,
+    terminal_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Orders {
+    orders: HashMap<String, TrackedOrder>,
+    // the orders in a terminal status, from the first finished to the last
+    finished: VecDeque<(Instant, String)>,
+}
+
+impl Orders {
+    /// Drops the orders in a terminal status for longer than the retention at
+    /// `now`
+    fn evict_expired(&mut self, now: Instant, retention: Duration) {
+        while let Some((terminal_at, _)) = self.finished.front() {
+            if now.saturating_duration_since(*terminal_at) < retention {
+                break;
+            }
+            let (terminal_at, order_id) = self.finished.pop_front().expect("front exists");
+            if matches!(
+                self.orders.get(&order_id),
+                Some(tracked) if tracked.terminal_at == Some(terminal_at)
+            ) {
+                self.orders.remove(&order_id);
+            }
+        }
+    }
+}
+
+struct Inner {
+    orders: Mutex<Orders>,
+    retention: Duration,
+    notify: Notify,
+}
+
+/// Tracks the latest state of the orders from the [`PushOrderChanged`] events
+///
+/// The tracker is cheap to clone, all clones share the same state.
+///
+/// The orders in a terminal status are dropped after the retention, see
+/// [`OrderTracker::with_retention`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{sync::Arc, time::Duration};
+///
+/// use longportwhale::{
+///     trade::{OrderTracker, SubscribeEventsOptions, TopicType, TradeContext},
+///     Config,
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let (ctx, _) = TradeContext::try_new(config).await?;
+/// let tracker = OrderTracker::from_events(ctx.subscribe_events(SubscribeEventsOptions::new()));
+/// ctx.subscribe([TopicType::Private]).await?;
+///
+/// let order = tracker
+///     .wait_for(
+///         "709043056541253632",
+///         |order| order.status.is_terminal(),
+///         Duration::from_secs(30),
+///     )
+///     .await;
+/// println!("{:?}", order);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct OrderTracker {
+    inner: Arc<Inner>,
+}
+
+impl Default for OrderTracker {
+    fn default() -> Self {
+        Self::with_retention(DEFAULT_RETENTION)
+    }
+}
+
+impl OrderTracker {
+    /// Create an empty `OrderTracker`, which keeps the orders in a terminal
+    /// status for 5 minutes
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create an empty `OrderTracker`, which keeps the orders in a terminal
+    /// status for `retention`
+    ///
+    /// The retention starts at the first event of an order in a terminal
+    /// status, the expired orders are dropped when the next event is ingested.
+    pub fn with_retention(retention: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                orders: Default::default(),
+                retention,
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// Create an `OrderTracker` that ingests the events of the receiver in a
+    /// background task, until the receiver is closed
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn from_events(receiver: EventReceiver) -> Self {
+        let tracker = Self::new();
+        tracker.track(receiver);
+        tracker
+    }
+
+    /// Ingests the events of the receiver in a background task, until the
+    /// receiver is closed
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn track(&self, mut receiver: EventReceiver) {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let PushEvent::OrderChanged(order_changed) = event {
+                    tracker.ingest(&order_changed);
+                }
+            }
+        });
+    }
+
+    /// Updates the state of an order
+    ///
+    /// The event is ignored if it is older than the tracked state, if it
+    /// reduces the executed quantity, or if the status cannot change to the
+    /// new one, e.g. from a terminal status, see
+    /// [`OrderStatus::is_terminal`](crate::trade::OrderStatus::is_terminal).
+    ///
+    /// Returns `true` if the state has been updated.
+    pub fn ingest(&self, event: &PushOrderChanged) -> bool {
+        let mut orders = self.inner.orders.lock();
+        orders.evict_expired(Instant::now(), self.inner.retention);
+
+        let mut terminal_at = None;
+        if let Some(TrackedOrder {
+            order: current,
+            terminal_at: current_terminal_at,
+        }) = orders.orders.get(&event.order_id)
+        {
+            if event.updated_at < current.updated_at
+                || event.executed_quantity < current.executed_quantity
+            {
+                tracing::debug!(
+                    order_id = event.order_id.as_str(),
+                    "ignored out-of-order order changed event"
+                );
+                return false;
+            }
+
+            if !current.status.can_transition_to(event.status) {
+                tracing::warn!(
+                    order_id = event.order_id.as_str(),
+                    from = %current.status,
+                    to = %event.status,
+                    "ignored invalid order status transition"
+                );
+                return false;
+            }
+
+            // an order is retained from the first event in a terminal status
+            terminal_at = *current_terminal_at;
+        }
+
+        if terminal_at.is_none() && event.status.is_terminal() {
+            let now = Instant::now();
+            orders.finished.push_back((now, event.order_id.clone()));
+            terminal_at = Some(now);
+        }
+        orders.orders.insert(
+            event.order_id.clone(),
+            TrackedOrder {
+                order: event.clone(),
+                terminal_at,
+            },
+        );
+        drop(orders);
+        self.inner.notify.notify_waiters();
+        true
+    }
+
+    /// Returns the latest state of an order
+    pub fn get(&self, order_id: &str) -> Option<PushOrderChanged> {
+        self.inner
+            .orders
+            .lock()
+            .orders
+            .get(order_id)
+            .map(|tracked| tracked.order.clone())
+    }
+
+    /// Returns the orders of an account that are not in a terminal status
+    pub fn open_orders(&self, account_no: &str) -> Vec<PushOrderChanged> {
+        self.inner
+            .orders
+            .lock()
+            .orders
+            .values()
+            .map(|tracked| &tracked.order)
+            .filter(|order| order.account_no == account_no && !order.status.is_terminal())
+            .cloned()
+            .collect()
+    }
+
+    /// Waits until the state of an order matches the predicate
+    ///
+    /// Returns `None` if it does not match before the timeout.
+    pub async fn wait_for<F>(
+        &self,
+        order_id: &str,
+        predicate: F,
+        timeout: Duration,
+    ) -> Option<PushOrderChanged>
+    where
+        F: Fn(&PushOrderChanged) -> bool,
+    {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.inner.notify.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+
+                if let Some(order) = self.get(order_id) {
+                    if predicate(&order) {
+                        return order;
+                    }
+                }
+
+                notified.await;
+            }
+        })
+        .await
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::trade::{push_types, OrderStatus};
+
+    fn order_changed(status: OrderStatus, executed_quantity: i64, second: u8) -> PushOrderChanged {
+        let mut event = push_types::tests::order_changed(status, executed_quantity);
+        event.updated_at = datetime!(2024-01-01 0:00 UTC)
+            .replace_second(second)
+            .unwrap();
+        event
+    }
+
+    #[test]
+    fn ingest() {
+        let tracker = OrderTracker::new();
+        assert!(tracker.ingest(&order_changed(OrderStatus::New, 0, 0)));
+        assert!(tracker.ingest(&order_changed(OrderStatus::PartialFilled, 100, 2)));
+        assert!(!tracker.ingest(&order_changed(OrderStatus::New, 0, 1)));
+        assert_eq!(tracker.open_orders("A").len(), 1);
+
+        assert!(tracker.ingest(&order_changed(OrderStatus::Filled, 200, 3)));
+        assert!(!tracker.ingest(&order_changed(OrderStatus::PartialFilled, 200, 4)));
+        assert_eq!(tracker.get("1").unwrap().status, OrderStatus::Filled);
+        assert!(tracker.open_orders("A").is_empty());
+    }
+
+    #[test]
+    fn retention() {
+        let tracker = OrderTracker::with_retention(Duration::ZERO);
+        assert!(tracker.ingest(&order_changed(OrderStatus::Filled, 200, 0)));
+        assert!(tracker.get("1").is_some());
+
+        let mut other = order_changed(OrderStatus::New, 0, 0);
+        other.order_id = "2".to_string();
+        assert!(tracker.ingest(&other));
+        assert!(tracker.get("1").is_none());
+        assert!(tracker.get("2").is_some());
+        assert!(tracker.inner.orders.lock().finished.is_empty());
+    }
+
+    #[test]
+    fn retain_from_first_terminal_event() {
+        let retention = Duration::from_secs(60);
+        let tracker = OrderTracker::with_retention(retention);
+        assert!(tracker.ingest(&order_changed(OrderStatus::Filled, 200, 0)));
+        // a duplicated event does not extend the retention
+        assert!(tracker.ingest(&order_changed(OrderStatus::Filled, 200, 1)));
+
+        let mut orders = tracker.inner.orders.lock();
+        assert_eq!(orders.finished.len(), 1);
+        let terminal_at = orders.finished[0].0;
+        assert_eq!(orders.orders["1"].terminal_at, Some(terminal_at));
+
+        orders.evict_expired(terminal_at + retention / 2, retention);
+        assert!(orders.orders.contains_key("1"));
+        orders.evict_expired(terminal_at + retention, retention);
+        assert!(orders.orders.is_empty());
+        assert!(orders.finished.is_empty());
+    }
+
+    #[tokio::test]
+    async fn wait_for() {
+        let tracker = OrderTracker::new();
+        tokio::spawn({
+            let tracker = tracker.clone();
+            async move {
+                tracker.ingest(&order_changed(OrderStatus::New, 0, 0));
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                tracker.ingest(&order_changed(OrderStatus::Filled, 200, 1));
+            }
+        });
+
+        let order = tracker
+            .wait_for(
+                "1",
+                |order| order.status.is_terminal(),
+                Duration::from_secs(1),
+            )
+            .await;
+        assert_eq!(order.unwrap().executed_quantity, 200);
+        assert!(tracker
+            .wait_for("2", |_| true, Duration::from_millis(10))
+            .await
+            .is_none());
+    }
+}
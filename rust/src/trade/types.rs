@@ -1,4 +1,9 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
 use strum_macros::{Display, EnumString};
+use time::{Date, OffsetDateTime};
+
+use crate::{serde_utils, Market, Symbol};
 
 /// Order type
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
@@ -107,6 +112,47 @@ pub enum OrderStatus {
     PartialWithdrawal,
 }
 
+impl OrderStatus {
+    /// Returns `true` if the order will not change anymore, i.e. it is
+    /// filled, rejected, canceled, expired or partially withdrawn
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled
+                | OrderStatus::Rejected
+                | OrderStatus::Canceled
+                | OrderStatus::Expired
+                | OrderStatus::PartialWithdrawal
+        )
+    }
+
+    /// Returns `true` if the order has not been reported to the exchange yet
+    pub(crate) fn is_not_reported(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::NotReported
+                | OrderStatus::ReplacedNotReported
+                | OrderStatus::ProtectedNotReported
+                | OrderStatus::VarietiesNotReported
+                | OrderStatus::WaitToNew
+        )
+    }
+
+    /// Returns `true` if an order can change from this status to `next`
+    ///
+    /// A terminal order never changes, and an order reported to the exchange
+    /// never goes back to a not reported status.
+    pub(crate) fn can_transition_to(&self, next: OrderStatus) -> bool {
+        if *self == next || *self == OrderStatus::Unknown || next == OrderStatus::Unknown {
+            return true;
+        }
+        if self.is_terminal() {
+            return false;
+        }
+        self.is_not_reported() || !next.is_not_reported()
+    }
+}
+
 /// Order side
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
 pub enum OrderSide {
@@ -167,6 +213,597 @@ pub enum TriggerStatus {
     Released,
 }
 
-impl_serde_for_enum_string!(OrderType, OrderStatus, OrderSide, OrderTag, TriggerStatus);
+/// Time in force type
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
+pub enum TimeInForceType {
+    /// Unknown
+    #[strum(disabled)]
+    Unknown,
+    /// Day Order
+    #[strum(serialize = "Day")]
+    Day,
+    /// Good Til Canceled Order
+    #[strum(serialize = "GTC")]
+    GoodTilCanceled,
+    /// Good Til Date Order
+    #[strum(serialize = "GTD")]
+    GoodTilDate,
+}
+
+/// Enable or disable outside regular trading hours
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
+pub enum OutsideRTH {
+    /// Unknown
+    #[strum(disabled)]
+    Unknown,
+    /// Regular trading hour only
+    #[strum(serialize = "RTH_ONLY")]
+    RTHOnly,
+    /// Any time
+    #[strum(serialize = "ANY_TIME")]
+    AnyTime,
+    /// Overnight
+    #[strum(serialize = "OVERNIGHT")]
+    Overnight,
+}
+
+/// Balance type
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
+pub enum BalanceType {
+    /// Unknown
+    #[strum(disabled)]
+    Unknown,
+    /// Cash
+    #[strum(serialize = "1")]
+    Cash,
+    /// Stock
+    #[strum(serialize = "2")]
+    Stock,
+    /// Fund
+    #[strum(serialize = "3")]
+    Fund,
+}
+
+/// Cash flow direction
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
+pub enum CashFlowDirection {
+    /// Unknown
+    #[strum(disabled)]
+    Unknown,
+    /// Out
+    #[strum(serialize = "1")]
+    Out,
+    /// In
+    #[strum(serialize = "2")]
+    In,
+}
+
+/// Order
+#[derive(Debug, Clone, Deserialize)]
+pub struct Order {
+    /// Order ID
+    pub order_id: String,
+    /// Order status
+    pub status: OrderStatus,
+    /// Stock name
+    pub stock_name: String,
+    /// Submitted quantity
+    #[serde(with = "serde_utils::int64_str")]
+    pub quantity: i64,
+    /// Executed quantity
+    #[serde(with = "serde_utils::int64_str")]
+    pub executed_quantity: i64,
+    /// Submitted price
+    #[serde(with = "serde_utils::decimal_opt_empty_is_none")]
+    pub price: Option<Decimal>,
+    /// Executed price
+    #[serde(with = "serde_utils::decimal_opt_0_is_none")]
+    pub executed_price: Option<Decimal>,
+    /// Submitted time
+    #[serde(with = "serde_utils::timestamp")]
+    pub submitted_at: OffsetDateTime,
+    /// Order side
+    pub side: OrderSide,
+    /// Order symbol
+    #[serde(with = "serde_utils::symbol_lenient")]
+    pub symbol: Symbol,
+    /// Order type
+    pub order_type: OrderType,
+    /// Last done
+    #[serde(with = "serde_utils::decimal_opt_empty_is_none")]
+    pub last_done: Option<Decimal>,
+    /// Order trigger price
+    #[serde(with = "serde_utils::decimal_opt_empty_is_none")]
+    pub trigger_price: Option<Decimal>,
+    /// Rejected message or remark
+    pub msg: String,
+    /// Order tag
+    pub tag: OrderTag,
+    /// Time in force type
+    pub time_in_force: TimeInForceType,
+    /// Long term order expire date
+    #[serde(with = "serde_utils::date_opt")]
+    pub expire_date: Option<Date>,
+    /// Last updated time
+    #[serde(with = "serde_utils::timestamp_opt")]
+    pub updated_at: Option<OffsetDateTime>,
+    /// Conditional order trigger time
+    #[serde(with = "serde_utils::timestamp_opt")]
+    pub trigger_at: Option<OffsetDateTime>,
+    /// Trailing amount
+    #[serde(with = "serde_utils::decimal_opt_empty_is_none")]
+    pub trailing_amount: Option<Decimal>,
+    /// Trailing percent
+    #[serde(with = "serde_utils::decimal_opt_empty_is_none")]
+    pub trailing_percent: Option<Decimal>,
+    /// Limit offset amount
+    #[serde(with = "serde_utils::decimal_opt_empty_is_none")]
+    pub limit_offset: Option<Decimal>,
+    /// Conditional order trigger status
+    #[serde(with = "serde_utils::trigger_status")]
+    pub trigger_status: Option<TriggerStatus>,
+    /// Currency
+    pub currency: String,
+    /// Enable or disable outside regular trading hours
+    #[serde(with = "serde_utils::outside_rth")]
+    pub outside_rth: Option<OutsideRTH>,
+    /// Account no
+    pub account_no: String,
+    /// Remark message
+    pub remark: String,
+}
+
+/// Execution
+#[derive(Debug, Clone, Deserialize)]
+pub struct Execution {
+    /// Order ID
+    pub order_id: String,
+    /// Execution ID
+    pub trade_id: String,
+    /// Security code
+    #[serde(with = "serde_utils::symbol_lenient")]
+    pub symbol: Symbol,
+    /// Trade done time
+    #[serde(with = "serde_utils::timestamp")]
+    pub trade_done_at: OffsetDateTime,
+    /// Executed quantity
+    #[serde(with = "serde_utils::int64_str")]
+    pub quantity: i64,
+    /// Executed price
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub price: Decimal,
+}
+
+/// Cash info
+#[derive(Debug, Clone, Deserialize)]
+pub struct CashInfo {
+    /// Withdraw cash
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub withdraw_cash: Decimal,
+    /// Available cash
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub available_cash: Decimal,
+    /// Frozen cash
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub frozen_cash: Decimal,
+    /// Cash to be settled
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub settling_cash: Decimal,
+    /// Currency
+    pub currency: String,
+}
+
+/// Account balance
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountBalance {
+    /// Total cash
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub total_cash: Decimal,
+    /// Maximum financing amount
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub max_finance_amount: Decimal,
+    /// Remaining financing amount
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub remaining_finance_amount: Decimal,
+    /// Risk control level
+    #[serde(with = "serde_utils::risk_level")]
+    pub risk_level: i32,
+    /// Margin call
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub margin_call: Decimal,
+    /// Currency
+    pub currency: String,
+    /// Cash details
+    #[serde(default)]
+    pub cash_infos: Vec<CashInfo>,
+    /// Net assets
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub net_assets: Decimal,
+    /// Initial margin
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub init_margin: Decimal,
+    /// Maintenance margin
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub maintenance_margin: Decimal,
+    /// Buy power
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub buy_power: Decimal,
+}
+
+/// Stock position
+#[derive(Debug, Clone, Deserialize)]
+pub struct StockPosition {
+    /// Stock code
+    #[serde(with = "serde_utils::symbol_lenient")]
+    pub symbol: Symbol,
+    /// Stock name
+    pub symbol_name: String,
+    /// The number of holdings
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub quantity: Decimal,
+    /// Available quantity
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub available_quantity: Decimal,
+    /// Currency
+    pub currency: String,
+    /// Cost Price(According to the client's choice of average purchase or
+    /// diluted cost)
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub cost_price: Decimal,
+    /// Market
+    pub market: Market,
+    /// Initial position before market opening
+    #[serde(default, with = "serde_utils::decimal_opt_empty_is_none")]
+    pub init_quantity: Option<Decimal>,
+}
+
+/// Stock position channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct StockPositionChannel {
+    /// Account type
+    pub account_channel: String,
+    /// Stock positions
+    #[serde(default)]
+    pub positions: Vec<StockPosition>,
+}
+
+/// Fund position
+#[derive(Debug, Clone, Deserialize)]
+pub struct FundPosition {
+    /// Fund ISIN code
+    pub symbol: String,
+    /// Current equity
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub current_net_asset_value: Decimal,
+    /// Current equity time
+    #[serde(with = "serde_utils::timestamp")]
+    pub net_asset_value_day: OffsetDateTime,
+    /// Fund name
+    pub symbol_name: String,
+    /// Currency
+    pub currency: String,
+    /// Net cost
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub cost_net_asset_value: Decimal,
+    /// Holding units
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub holding_units: Decimal,
+}
+
+/// Fund position channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct FundPositionChannel {
+    /// Account type
+    pub account_channel: String,
+    /// Fund positions
+    #[serde(default, rename = "fund_info")]
+    pub positions: Vec<FundPosition>,
+}
+
+/// Cash flow
+#[derive(Debug, Clone, Deserialize)]
+pub struct CashFlow {
+    /// Cash flow name
+    pub transaction_flow_name: String,
+    /// Outflow direction
+    #[serde(with = "serde_utils::enum_str_or_int")]
+    pub direction: CashFlowDirection,
+    /// Balance type
+    #[serde(with = "serde_utils::enum_str_or_int")]
+    pub business_type: BalanceType,
+    /// Cash amount
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub balance: Decimal,
+    /// Cash currency
+    pub currency: String,
+    /// Business time
+    #[serde(with = "serde_utils::timestamp")]
+    pub business_time: OffsetDateTime,
+    /// Associated Stock code information
+    #[serde(default, with = "serde_utils::symbol_opt")]
+    pub symbol: Option<Symbol>,
+    /// Cash flow description
+    pub description: String,
+}
+
+/// Response for estimate maximum purchase quantity
+#[derive(Debug, Clone, Deserialize)]
+pub struct EstimateMaxPurchaseQuantityResponse {
+    /// Cash available quantity
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub cash_max_qty: Decimal,
+    /// Margin available quantity
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub margin_max_qty: Decimal,
+}
+
+/// Margin ratio
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarginRatio {
+    /// Initial margin ratio
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub im_factor: Decimal,
+    /// Maintain the initial margin ratio
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub mm_factor: Decimal,
+    /// Forced close-out margin ratio
+    #[serde(with = "serde_utils::decimal_empty_is_0")]
+    pub fm_factor: Decimal,
+}
+
+impl_serde_for_enum_string!(
+    OrderType,
+    OrderStatus,
+    OrderSide,
+    OrderTag,
+    TriggerStatus,
+    TimeInForceType,
+    OutsideRTH,
+    BalanceType,
+    CashFlowDirection
+);
+
+impl_default_for_enum_string!(
+    OrderType,
+    OrderStatus,
+    OrderSide,
+    OrderTag,
+    TriggerStatus,
+    TimeInForceType,
+    OutsideRTH,
+    BalanceType,
+    CashFlowDirection
+);
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+
+    #[test]
+    fn deserialize_order() {
+        let data = r#"{
+            "order_id": "706388312699592704",
+            "status": "RejectedStatus",
+            "stock_name": "Tencent",
+            "quantity": "200",
+            "executed_quantity": "0",
+            "price": "300",
+            "executed_price": "0",
+            "submitted_at": "1651644897",
+            "side": "Buy",
+            "symbol": "700.HK",
+            "order_type": "LO",
+            "last_done": "",
+            "trigger_price": "",
+            "msg": "Insufficient buying power",
+            "tag": "GTC",
+            "time_in_force": "GTD",
+            "expire_date": "2022-06-30",
+            "updated_at": "1651644898",
+            "trigger_at": "0",
+            "trailing_amount": "",
+            "trailing_percent": "",
+            "limit_offset": "",
+            "trigger_status": "NOT_USED",
+            "currency": "HKD",
+            "outside_rth": "UnknownOutsideRth",
+            "account_no": "L6VQEU00121996",
+            "remark": ""
+        }"#;
 
-impl_default_for_enum_string!(OrderType, OrderStatus, OrderSide, OrderTag, TriggerStatus);
+        let order: Order = serde_json::from_str(data).unwrap();
+        assert_eq!(order.order_id, "706388312699592704");
+        assert_eq!(order.status, OrderStatus::Rejected);
+        assert_eq!(order.quantity, 200);
+        assert_eq!(order.price, Some(decimal!(300i32)));
+        assert_eq!(order.executed_price, None);
+        assert_eq!(order.submitted_at, datetime!(2022-05-04 06:14:57 UTC));
+        assert_eq!(order.symbol, "700.HK".parse().unwrap());
+        assert_eq!(order.order_type, OrderType::LO);
+        assert_eq!(order.last_done, None);
+        assert_eq!(order.tag, OrderTag::LongTerm);
+        assert_eq!(order.time_in_force, TimeInForceType::GoodTilDate);
+        assert_eq!(order.expire_date, Some(date!(2022 - 06 - 30)));
+        assert_eq!(order.updated_at, Some(datetime!(2022-05-04 06:14:58 UTC)));
+        assert_eq!(order.trigger_at, None);
+        assert_eq!(order.trigger_status, None);
+        assert_eq!(order.outside_rth, None);
+    }
+
+    #[test]
+    fn deserialize_execution() {
+        let data = r#"{
+            "order_id": "693664675163312128",
+            "trade_id": "693664675163312128-1648611351433741210",
+            "symbol": "700.HK",
+            "trade_done_at": "1648611351",
+            "quantity": "100",
+            "price": "473.600"
+        }"#;
+
+        let execution: Execution = serde_json::from_str(data).unwrap();
+        assert_eq!(execution.order_id, "693664675163312128");
+        assert_eq!(execution.trade_id, "693664675163312128-1648611351433741210");
+        assert_eq!(execution.symbol, "700.HK".parse().unwrap());
+        assert_eq!(execution.trade_done_at, datetime!(2022-03-30 03:35:51 UTC));
+        assert_eq!(execution.quantity, 100);
+        assert_eq!(execution.price, decimal!(473.6f64));
+    }
+
+    #[test]
+    fn deserialize_account_balance() {
+        let data = r#"{
+            "total_cash": "1759070010.72",
+            "max_finance_amount": "977582000",
+            "remaining_finance_amount": "",
+            "risk_level": "1",
+            "margin_call": "0",
+            "currency": "HKD",
+            "cash_infos": [
+                {
+                    "withdraw_cash": "97592.30",
+                    "available_cash": "195902464.37",
+                    "frozen_cash": "11579339.13",
+                    "settling_cash": "",
+                    "currency": "HKD"
+                }
+            ],
+            "net_assets": "1759070010.72",
+            "init_margin": "0",
+            "maintenance_margin": "0",
+            "buy_power": "1759070010.72"
+        }"#;
+
+        let balance: AccountBalance = serde_json::from_str(data).unwrap();
+        assert_eq!(balance.total_cash, decimal!(1759070010.72f64));
+        assert_eq!(balance.remaining_finance_amount, Decimal::ZERO);
+        assert_eq!(balance.risk_level, 1);
+        assert_eq!(balance.currency, "HKD");
+        assert_eq!(balance.cash_infos.len(), 1);
+        assert_eq!(
+            balance.cash_infos[0].available_cash,
+            decimal!(195902464.37f64)
+        );
+        assert_eq!(balance.cash_infos[0].settling_cash, Decimal::ZERO);
+
+        // the risk level is either a string or a number
+        for (risk_level, expected) in [("\"\"", 0), ("\"2\"", 2), ("3", 3)] {
+            let data = data.replace(
+                r#""risk_level": "1""#,
+                &format!(r#""risk_level": {risk_level}"#),
+            );
+            let balance: AccountBalance = serde_json::from_str(&data).unwrap();
+            assert_eq!(balance.risk_level, expected);
+        }
+    }
+
+    #[test]
+    fn deserialize_positions() {
+        let data = r#"{
+            "account_channel": "lb",
+            "positions": [
+                {
+                    "symbol": "700.HK",
+                    "symbol_name": "Tencent",
+                    "quantity": "650",
+                    "available_quantity": "-450",
+                    "currency": "HKD",
+                    "cost_price": "457.53",
+                    "market": "HK",
+                    "init_quantity": ""
+                }
+            ]
+        }"#;
+
+        let channel: StockPositionChannel = serde_json::from_str(data).unwrap();
+        assert_eq!(channel.account_channel, "lb");
+        let position = &channel.positions[0];
+        assert_eq!(position.symbol, "700.HK".parse().unwrap());
+        assert_eq!(position.quantity, decimal!(650i32));
+        assert_eq!(position.available_quantity, decimal!(-450i32));
+        assert_eq!(position.cost_price, decimal!(457.53f64));
+        assert_eq!(position.market, Market::HK);
+        assert_eq!(position.init_quantity, None);
+
+        let data = r#"{
+            "account_channel": "lb",
+            "fund_info": [
+                {
+                    "symbol": "HK0000447943",
+                    "current_net_asset_value": "0.0000",
+                    "net_asset_value_day": "1649865600",
+                    "symbol_name": "High-Yield Bond Fund",
+                    "currency": "USD",
+                    "cost_net_asset_value": "0.00",
+                    "holding_units": "5.000"
+                }
+            ]
+        }"#;
+
+        let channel: FundPositionChannel = serde_json::from_str(data).unwrap();
+        assert_eq!(channel.account_channel, "lb");
+        let position = &channel.positions[0];
+        assert_eq!(position.symbol, "HK0000447943");
+        assert_eq!(
+            position.net_asset_value_day,
+            datetime!(2022-04-13 16:00:00 UTC)
+        );
+        assert_eq!(position.holding_units, decimal!(5i32));
+    }
+
+    #[test]
+    fn deserialize_cash_flow() {
+        let data = r#"{
+            "transaction_flow_name": "BuyContract-Stocks",
+            "direction": 1,
+            "business_type": 2,
+            "balance": "-248.60",
+            "currency": "USD",
+            "business_time": "1621507957",
+            "symbol": "AAPL.US",
+            "description": "AAPL"
+        }"#;
+
+        let cash_flow: CashFlow = serde_json::from_str(data).unwrap();
+        assert_eq!(cash_flow.transaction_flow_name, "BuyContract-Stocks");
+        assert_eq!(cash_flow.direction, CashFlowDirection::Out);
+        assert_eq!(cash_flow.business_type, BalanceType::Stock);
+        assert_eq!(cash_flow.balance, decimal!(-248.6f64));
+        assert_eq!(cash_flow.business_time, datetime!(2021-05-20 10:52:37 UTC));
+        assert_eq!(cash_flow.symbol, Some("AAPL.US".parse().unwrap()));
+
+        // a cash flow without an associated stock
+        let data = data.replace(r#""symbol": "AAPL.US""#, r#""symbol": """#);
+        let cash_flow: CashFlow = serde_json::from_str(&data).unwrap();
+        assert_eq!(cash_flow.symbol, None);
+
+        // the direction and the balance type are either numbers or strings
+        let data = data
+            .replace(r#""direction": 1"#, r#""direction": "2""#)
+            .replace(r#""business_type": 2"#, r#""business_type": "1""#);
+        let cash_flow: CashFlow = serde_json::from_str(&data).unwrap();
+        assert_eq!(cash_flow.direction, CashFlowDirection::In);
+        assert_eq!(cash_flow.business_type, BalanceType::Cash);
+    }
+
+    #[test]
+    fn deserialize_estimate_max_purchase_quantity() {
+        let data = r#"{"cash_max_qty": "100", "margin_max_qty": ""}"#;
+
+        let resp: EstimateMaxPurchaseQuantityResponse = serde_json::from_str(data).unwrap();
+        assert_eq!(resp.cash_max_qty, decimal!(100i32));
+        assert_eq!(resp.margin_max_qty, Decimal::ZERO);
+    }
+
+    #[test]
+    fn deserialize_margin_ratio() {
+        let data = r#"{"im_factor": "0.6", "mm_factor": "0.5", "fm_factor": "0.45"}"#;
+
+        let ratio: MarginRatio = serde_json::from_str(data).unwrap();
+        assert_eq!(ratio.im_factor, decimal!(0.6f64));
+        assert_eq!(ratio.mm_factor, decimal!(0.5f64));
+        assert_eq!(ratio.fm_factor, decimal!(0.45f64));
+    }
+}
@@ -1,5 +1,10 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use strum_macros::{Display, EnumString};
 
+use crate::Error;
+
 /// Market
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, Display)]
 pub enum Market {
@@ -18,3 +23,182 @@ pub enum Market {
 
 impl_default_for_enum_string!(Market);
 impl_serde_for_enum_string!(Market);
+
+/// Security symbol, e.g. `700.HK`, `AAPL.US` or `600519.SH`
+///
+/// A symbol is made of a security code and a market suffix separated by the
+/// last `.`. Both parts are normalised to upper case when parsing, and the
+/// suffix must be one of `US`, `HK`, `SH`, `SZ` or `SG`, otherwise
+/// [`Error::InvalidSecuritySymbol`] is returned.
+///
+/// Deserializing a symbol is as strict as parsing it. The symbols returned by
+/// the server are not validated: a symbol that cannot be parsed is kept as
+/// is, with [`Market::Unknown`].
+///
+/// # Examples
+///
+/// ```
+/// use longportwhale::{Market, Symbol};
+///
+/// let symbol: Symbol = "aapl.us".parse().unwrap();
+/// assert_eq!(symbol.code(), "AAPL");
+/// assert_eq!(symbol.market(), Market::US);
+/// assert_eq!(symbol.to_string(), "AAPL.US");
+/// ```
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Symbol {
+    symbol: String,
+    code_len: usize,
+    market: Market,
+}
+
+impl Symbol {
+    /// Parses a symbol returned by the server, which is kept as is with
+    /// [`Market::Unknown`] if it is not valid
+    pub(crate) fn parse_lenient(symbol: String) -> Self {
+        symbol.parse().unwrap_or_else(|_| Self {
+            code_len: symbol.rfind('.').unwrap_or(symbol.len()),
+            symbol,
+            market: Market::Unknown,
+        })
+    }
+
+    /// Returns the security code, e.g. `700` for `700.HK`
+    #[inline]
+    pub fn code(&self) -> &str {
+        &self.symbol[..self.code_len]
+    }
+
+    /// Returns the market of the security
+    #[inline]
+    pub fn market(&self) -> Market {
+        self.market
+    }
+}
+
+impl FromStr for Symbol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidSecuritySymbol {
+            symbol: s.to_string(),
+        };
+
+        let (code, suffix) = s.rsplit_once('.').ok_or_else(invalid)?;
+        if code.is_empty()
+            || !code
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        {
+            return Err(invalid());
+        }
+
+        let (suffix, market) = match suffix.to_ascii_uppercase().as_str() {
+            "US" => ("US", Market::US),
+            "HK" => ("HK", Market::HK),
+            "SH" => ("SH", Market::CN),
+            "SZ" => ("SZ", Market::CN),
+            "SG" => ("SG", Market::SG),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            symbol: format!("{}.{suffix}", code.to_ascii_uppercase()),
+            code_len: code.len(),
+            market,
+        })
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.symbol)
+    }
+}
+
+impl From<Symbol> for String {
+    #[inline]
+    fn from(symbol: Symbol) -> Self {
+        symbol.symbol
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_symbol() {
+        let symbol: Symbol = "700.hk".parse().unwrap();
+        assert_eq!(symbol.code(), "700");
+        assert_eq!(symbol.market(), Market::HK);
+        assert_eq!(symbol.to_string(), "700.HK");
+
+        let symbol: Symbol = "BRK.B.US".parse().unwrap();
+        assert_eq!(symbol.code(), "BRK.B");
+        assert_eq!(symbol.market(), Market::US);
+
+        let symbol: Symbol = "600519.SH".parse().unwrap();
+        assert_eq!(symbol.market(), Market::CN);
+        assert_eq!(symbol.to_string(), "600519.SH");
+    }
+
+    #[test]
+    fn parse_invalid_symbol() {
+        for s in ["", "700", ".HK", "700.", "700.XX", "7 00.HK", "700.CN"] {
+            assert!(
+                matches!(
+                    s.parse::<Symbol>(),
+                    Err(Error::InvalidSecuritySymbol { symbol }) if symbol == s
+                ),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn serde_symbol() {
+        let symbol: Symbol = serde_json::from_str("\"aapl.us\"").unwrap();
+        assert_eq!(serde_json::to_string(&symbol).unwrap(), "\"AAPL.US\"");
+
+        for s in ["AAPL", "D05.SI", "7203.t"] {
+            assert!(serde_json::from_str::<Symbol>(&format!("\"{s}\"")).is_err());
+        }
+    }
+
+    #[test]
+    fn serde_symbol_lenient() {
+        #[derive(Deserialize)]
+        struct Data {
+            #[serde(with = "crate::serde_utils::symbol_lenient")]
+            symbol: Symbol,
+        }
+
+        let data: Data = serde_json::from_str(r#"{"symbol": "aapl.us"}"#).unwrap();
+        assert_eq!(data.symbol.market(), Market::US);
+        assert_eq!(data.symbol.to_string(), "AAPL.US");
+
+        // the symbols returned by the server are kept as is
+        for s in ["AAPL", "D05.SI", "7203.t"] {
+            let data: Data = serde_json::from_str(&format!(r#"{{"symbol": "{s}"}}"#)).unwrap();
+            assert_eq!(data.symbol.market(), Market::Unknown);
+            assert_eq!(data.symbol.to_string(), s);
+        }
+        let data: Data = serde_json::from_str(r#"{"symbol": "D05.SI"}"#).unwrap();
+        assert_eq!(data.symbol.code(), "D05");
+    }
+}